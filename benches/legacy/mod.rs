@@ -0,0 +1,126 @@
+//! The resolution path of rust_di 3.1, kept as a baseline for `resolve`:
+//! `(type_name, name)` `String` keys, one table per lifetime looked up in
+//! order and a resolving stack of type names.
+
+use arc_swap::ArcSwap;
+use dashmap::DashMap;
+use std::any::Any;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type Instance = Arc<dyn Any + Send + Sync>;
+type Key = (String, String);
+type Factory = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Instance> + Send>> + Send + Sync>;
+type FactoryMap = DashMap<Key, Factory>;
+
+tokio::task_local! {
+    static RESOLVING_STACK: RefCell<Vec<String>>;
+}
+
+#[derive(Clone, Copy)]
+pub enum Lifetime {
+    Singleton,
+    Scoped,
+    Transient,
+}
+
+#[derive(Default)]
+pub struct Container {
+    singleton_factories: ArcSwap<FactoryMap>,
+    scope_factories: ArcSwap<FactoryMap>,
+    transient_factories: ArcSwap<FactoryMap>,
+    singleton_cache: DashMap<Key, Instance>,
+}
+
+impl Container {
+    pub fn register<T, F>(&self, lifetime: Lifetime, name: &str, factory: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        let factory = Arc::new(factory);
+        let factory: Factory = Arc::new(move || {
+            let factory = factory.clone();
+            Box::pin(async move { Arc::new(factory()) as Instance })
+        });
+        let factories = match lifetime {
+            Lifetime::Singleton => &self.singleton_factories,
+            Lifetime::Scoped => &self.scope_factories,
+            Lifetime::Transient => &self.transient_factories,
+        };
+        factories.load().insert(
+            (std::any::type_name::<T>().to_string(), name.to_string()),
+            factory,
+        );
+    }
+}
+
+pub struct Scope {
+    container: Arc<Container>,
+    scoped_instances: DashMap<Key, Instance>,
+}
+
+impl Scope {
+    pub fn new(container: Arc<Container>) -> Self {
+        Self {
+            container,
+            scoped_instances: DashMap::new(),
+        }
+    }
+
+    pub async fn run<F: Future>(fut: F) -> F::Output {
+        RESOLVING_STACK.scope(RefCell::new(Vec::new()), fut).await
+    }
+
+    pub async fn get_by_name<T: Send + Sync + 'static>(&self, name: &str) -> Option<Arc<T>> {
+        let type_key = std::any::type_name::<T>().to_string();
+        let name_string = name.to_string();
+        let key = (type_key.clone(), name_string.clone());
+
+        RESOLVING_STACK
+            .try_with(|stack| {
+                let mut stack_ref = stack.borrow_mut();
+                if stack_ref.contains(&type_key) {
+                    return None;
+                }
+                stack_ref.push(type_key.clone());
+                Some(())
+            })
+            .ok()??;
+
+        let result = async {
+            if let Some(entry) = self.scoped_instances.get(&key) {
+                return Some(entry.value().clone());
+            }
+            if let Some(factory) = self.container.scope_factories.load().get(&key) {
+                let instance = factory.value()().await;
+                self.scoped_instances.insert(key.clone(), instance.clone());
+                return Some(instance);
+            }
+
+            if let Some(factory) = self.container.singleton_factories.load().get(&key) {
+                if let Some(cached) = self.container.singleton_cache.get(&key) {
+                    return Some(cached.value().clone());
+                }
+                let instance = factory.value()().await;
+                self.container
+                    .singleton_cache
+                    .insert(key.clone(), instance.clone());
+                return Some(instance);
+            }
+
+            if let Some(factory) = self.container.transient_factories.load().get(&key) {
+                return Some(factory.value()().await);
+            }
+
+            None
+        }
+        .await;
+
+        let _ = RESOLVING_STACK.try_with(|stack| stack.borrow_mut().pop());
+
+        result?.downcast::<T>().ok()
+    }
+}
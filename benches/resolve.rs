@@ -0,0 +1,218 @@
+//! Cost of resolving already registered services: `cargo bench --bench resolve`.
+//!
+//! Every benchmark runs against the current implementation and against
+//! [`legacy`], the 3.1 resolution path, on the same services.
+
+mod legacy;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rust_di::DIScope;
+use rust_di::core::container::Container;
+use rust_di::core::registry::{
+    register_scope_name, register_singleton_name, register_transient_name,
+};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+
+/// Services resolved by one request in `request_30`, per lifetime.
+const PER_LIFETIME: usize = 10;
+
+struct Config {
+    _port: u16,
+}
+
+struct RequestContext {
+    _id: u64,
+}
+
+struct Command {
+    _attempt: u32,
+}
+
+fn names() -> Vec<String> {
+    (0..PER_LIFETIME).map(|i| format!("service_{i}")).collect()
+}
+
+fn setup(runtime: &Runtime) -> (Arc<Container>, Arc<legacy::Container>) {
+    let container = Container::new();
+    let legacy = Arc::new(legacy::Container::default());
+
+    runtime.block_on(container.clone().run(async {
+        for name in std::iter::once(String::new()).chain(names()) {
+            register_singleton_name::<Config, _, _>(&name, |_| async { Ok(Config { _port: 80 }) })
+                .await
+                .unwrap();
+            register_scope_name::<RequestContext, _, _>(&name, |_| async {
+                Ok(RequestContext { _id: 1 })
+            })
+            .await
+            .unwrap();
+            register_transient_name::<Command, _, _>(&name, |_| async {
+                Ok(Command { _attempt: 0 })
+            })
+            .await
+            .unwrap();
+
+            legacy.register(legacy::Lifetime::Singleton, &name, || Config { _port: 80 });
+            legacy.register(legacy::Lifetime::Scoped, &name, || RequestContext {
+                _id: 1,
+            });
+            legacy.register(legacy::Lifetime::Transient, &name, || Command {
+                _attempt: 0,
+            });
+        }
+    }));
+
+    (container, legacy)
+}
+
+/// Runs `get` `iters` times inside one scope, which is created once.
+async fn time_current<F, Fut>(container: Arc<Container>, iters: u64, get: F) -> Duration
+where
+    F: Fn(Arc<DIScope>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    container
+        .run(DIScope::run_with_scope(|| async {
+            let scope = DIScope::current().unwrap();
+            let started = Instant::now();
+            for _ in 0..iters {
+                get(scope.clone()).await;
+            }
+            started.elapsed()
+        }))
+        .await
+}
+
+async fn time_legacy<F, Fut>(container: Arc<legacy::Container>, iters: u64, get: F) -> Duration
+where
+    F: Fn(Arc<legacy::Scope>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    legacy::Scope::run(async {
+        let scope = Arc::new(legacy::Scope::new(container));
+        let started = Instant::now();
+        for _ in 0..iters {
+            get(scope.clone()).await;
+        }
+        started.elapsed()
+    })
+    .await
+}
+
+fn resolve(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let (container, legacy) = setup(&runtime);
+    let mut group = c.benchmark_group("resolve");
+
+    for name in ["", "service_0"] {
+        let label = if name.is_empty() { "default" } else { "named" };
+
+        group.bench_with_input(BenchmarkId::new("singleton", label), name, |b, name| {
+            b.to_async(&runtime).iter_custom(|iters| {
+                time_current(container.clone(), iters, move |scope| async move {
+                    black_box(scope.get_by_name::<Config>(name).await.unwrap());
+                })
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("singleton_legacy", label),
+            name,
+            |b, name| {
+                b.to_async(&runtime).iter_custom(|iters| {
+                    time_legacy(legacy.clone(), iters, move |scope| async move {
+                        black_box(scope.get_by_name::<Config>(name).await.unwrap());
+                    })
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("scoped", label), name, |b, name| {
+            b.to_async(&runtime).iter_custom(|iters| {
+                time_current(container.clone(), iters, move |scope| async move {
+                    black_box(scope.get_by_name::<RequestContext>(name).await.unwrap());
+                })
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("scoped_legacy", label), name, |b, name| {
+            b.to_async(&runtime).iter_custom(|iters| {
+                time_legacy(legacy.clone(), iters, move |scope| async move {
+                    black_box(scope.get_by_name::<RequestContext>(name).await.unwrap());
+                })
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("transient", label), name, |b, name| {
+            b.to_async(&runtime).iter_custom(|iters| {
+                time_current(container.clone(), iters, move |scope| async move {
+                    black_box(scope.get_by_name::<Command>(name).await.unwrap());
+                })
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("transient_legacy", label),
+            name,
+            |b, name| {
+                b.to_async(&runtime).iter_custom(|iters| {
+                    time_legacy(legacy.clone(), iters, move |scope| async move {
+                        black_box(scope.get_by_name::<Command>(name).await.unwrap());
+                    })
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// A request opening a scope and resolving 30 services, 10 of each lifetime.
+fn request_30(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let (container, legacy) = setup(&runtime);
+    let names = Arc::new(names());
+    let mut group = c.benchmark_group("request_30");
+
+    group.bench_function("current", |b| {
+        b.to_async(&runtime).iter(|| {
+            let names = names.clone();
+            container
+                .clone()
+                .run(DIScope::run_with_scope(move || async move {
+                    let scope = DIScope::current().unwrap();
+                    for name in names.iter() {
+                        black_box(scope.clone().get_by_name::<Config>(name).await.unwrap());
+                        black_box(
+                            scope
+                                .clone()
+                                .get_by_name::<RequestContext>(name)
+                                .await
+                                .unwrap(),
+                        );
+                        black_box(scope.clone().get_by_name::<Command>(name).await.unwrap());
+                    }
+                }))
+        })
+    });
+
+    group.bench_function("legacy", |b| {
+        b.to_async(&runtime).iter(|| {
+            let names = names.clone();
+            let legacy = legacy.clone();
+            legacy::Scope::run(async move {
+                let scope = legacy::Scope::new(legacy);
+                for name in names.iter() {
+                    black_box(scope.get_by_name::<Config>(name).await.unwrap());
+                    black_box(scope.get_by_name::<RequestContext>(name).await.unwrap());
+                    black_box(scope.get_by_name::<Command>(name).await.unwrap());
+                }
+            })
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, resolve, request_30);
+criterion_main!(benches);
@@ -0,0 +1,252 @@
+use crate::injection::{inject_source, is_inject_attr, parse_inject_args, resolve_expr};
+use crate::register_macros::{
+    DiKind, Ordering, constructor, parse_retry, parse_timeout, registration_options,
+};
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{
+    Expr, ExprLit, FnArg, GenericArgument, ItemFn, Lit, LitStr, Meta, PathArguments, ReturnType,
+    Token, Type, parse_macro_input,
+};
+
+pub(crate) fn expand_factory(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut input_fn = parse_macro_input!(item as ItemFn);
+
+    let registration = match expand(attr, &mut input_fn) {
+        Ok(registration) => registration,
+        Err(err) => err.to_compile_error(),
+    };
+
+    quote! {
+        #input_fn
+        #registration
+    }
+    .into()
+}
+
+fn expand(attr: TokenStream, input_fn: &mut ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let args = parse_factory_args(attr)?;
+    let sig = &input_fn.sig;
+
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "`#[rust_di::factory]` can only be applied to async functions",
+        ));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "`#[rust_di::factory]` functions cannot be generic",
+        ));
+    }
+
+    let (service_ty, fallible) = service_type(&sig.output)?;
+    let fn_ident = sig.ident.clone();
+
+    let mut resolutions = Vec::new();
+    let mut arguments = Vec::new();
+    for (index, input) in input_fn.sig.inputs.iter_mut().enumerate() {
+        let FnArg::Typed(param) = input else {
+            return Err(syn::Error::new_spanned(
+                input,
+                "`#[rust_di::factory]` must be applied to a free function",
+            ));
+        };
+
+        let inject_args = parse_inject_args(&param.attrs)?;
+        let source = inject_source(&param.ty, &inject_args)?;
+        let value = resolve_expr(&source, inject_args.name.as_ref());
+        param.attrs.retain(|attr| !is_inject_attr(attr));
+
+        let argument = format_ident!("__rust_di_arg{}", index);
+        resolutions.push(quote!(let #argument = #value;));
+        arguments.push(argument);
+    }
+
+    let call = if fallible {
+        quote!(#fn_ident(#(#arguments),*).await)
+    } else {
+        quote!(Ok(#fn_ident(#(#arguments),*).await))
+    };
+
+    let register = match (&args.kind, args.eager) {
+        (DiKind::Singleton, true) => quote!(register_singleton_name_eager),
+        (DiKind::Singleton, false) => quote!(register_singleton_name),
+        (DiKind::Scoped, false) => quote!(register_scope_name),
+        (DiKind::Transient, false) => quote!(register_transient_name),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &args.kind_ident,
+                "`eager` is only supported for `Singleton` registrations",
+            ));
+        }
+    };
+    if args.retry.is_some() && args.kind != DiKind::Singleton {
+        return Err(syn::Error::new_spanned(
+            &args.kind_ident,
+            "`retry` is only supported for `Singleton` registrations",
+        ));
+    }
+    let name = &args.name;
+    let options = registration_options(args.if_missing, args.timeout, args.retry.as_ref(), None);
+
+    Ok(constructor(
+        quote! {
+            |batch| batch.add(
+                ::rust_di::core::registry::#register::<#service_ty, _, _>(
+                    #name,
+                    |scope: ::std::sync::Arc<::rust_di::DIScope>| async move {
+                        #(#resolutions)*
+                        #call
+                    },
+                )
+                #options
+            )
+        },
+        &service_ty,
+        name,
+        &Ordering::default(),
+    ))
+}
+
+struct FactoryArgs {
+    kind: DiKind,
+    kind_ident: syn::Ident,
+    name: LitStr,
+    eager: bool,
+    if_missing: bool,
+    timeout: Option<u64>,
+    retry: Option<proc_macro2::TokenStream>,
+}
+
+fn parse_factory_args(attr: TokenStream) -> syn::Result<FactoryArgs> {
+    let metas = Punctuated::<Meta, Token![,]>::parse_terminated.parse(attr)?;
+
+    let mut kind = None;
+    let mut name = None;
+    let mut eager = false;
+    let mut if_missing = false;
+    let mut timeout = None;
+    let mut retry = None;
+
+    for meta in metas {
+        match &meta {
+            Meta::Path(path)
+                if path.is_ident("Singleton")
+                    || path.is_ident("Scoped")
+                    || path.is_ident("Transient") =>
+            {
+                if kind.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "only one lifetime can be specified",
+                    ));
+                }
+                kind = path.get_ident().cloned();
+            }
+            Meta::Path(path) if path.is_ident("eager") => {
+                if eager {
+                    return Err(syn::Error::new_spanned(path, "duplicate `eager` option"));
+                }
+                eager = true;
+            }
+            Meta::Path(path) if path.is_ident("if_missing") => {
+                if if_missing {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "duplicate `if_missing` option",
+                    ));
+                }
+                if_missing = true;
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("timeout") => {
+                if timeout.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &nv.path,
+                        "duplicate `timeout` option",
+                    ));
+                }
+                timeout = Some(parse_timeout(&nv.value)?);
+            }
+            Meta::List(list) if list.path.is_ident("retry") => {
+                if retry.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &list.path,
+                        "duplicate `retry` option",
+                    ));
+                }
+                retry = Some(parse_retry(list)?);
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("name") => {
+                if name.is_some() {
+                    return Err(syn::Error::new_spanned(&nv.path, "duplicate `name` option"));
+                }
+                match &nv.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit_str),
+                        ..
+                    }) => name = Some(lit_str.clone()),
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected a string literal, like `name = \"primary\"`",
+                        ));
+                    }
+                }
+            }
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "expected `Singleton`, `Scoped`, `Transient`, `name = \"...\"`, `eager`, `if_missing`, `timeout = \"...\"` or `retry(...)`",
+                ));
+            }
+        }
+    }
+
+    let kind_ident = kind.ok_or_else(|| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "missing lifetime: `#[rust_di::factory(Singleton)]`, `Scoped` or `Transient`",
+        )
+    })?;
+
+    let kind = match kind_ident.to_string().as_str() {
+        "Singleton" => DiKind::Singleton,
+        "Scoped" => DiKind::Scoped,
+        _ => DiKind::Transient,
+    };
+
+    Ok(FactoryArgs {
+        kind,
+        kind_ident,
+        name: name.unwrap_or_else(|| LitStr::new("", proc_macro2::Span::call_site())),
+        eager,
+        if_missing,
+        timeout,
+        retry,
+    })
+}
+
+/// Registered type: `T` for `Result<T, _>` (fallible) or the return type itself.
+fn service_type(output: &ReturnType) -> syn::Result<(Type, bool)> {
+    let ReturnType::Type(_, ty) = output else {
+        return Err(syn::Error::new_spanned(
+            output,
+            "`#[rust_di::factory]` functions must return the service, e.g. `Result<MyService, DiError>`",
+        ));
+    };
+
+    if let Type::Path(type_path) = &**ty
+        && let Some(segment) = type_path.path.segments.last()
+        && segment.ident == "Result"
+        && let PathArguments::AngleBracketed(arguments) = &segment.arguments
+        && let Some(GenericArgument::Type(service)) = arguments.args.first()
+    {
+        return Ok((service.clone(), true));
+    }
+
+    Ok(((**ty).clone(), false))
+}
@@ -0,0 +1,130 @@
+use crate::injection::{
+    InjectSource, generic_argument, inject_source, is_inject_attr, parse_inject_args, resolve_expr,
+};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{FnArg, ItemFn, PatType, ReturnType, Signature, Token, Type, parse_macro_input};
+
+pub(crate) fn expand_inject(item: TokenStream) -> TokenStream {
+    let mut input_fn = parse_macro_input!(item as ItemFn);
+
+    if input_fn.sig.asyncness.is_none() {
+        return syn::Error::new_spanned(
+            input_fn.sig.fn_token,
+            "`#[rust_di::inject]` can only be applied to async functions",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let prelude = match take_injected_params(&mut input_fn.sig) {
+        Ok(prelude) => prelude,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let fn_attrs = &input_fn.attrs;
+    let fn_vis = &input_fn.vis;
+    let fn_sig = &input_fn.sig;
+    let fn_block = &input_fn.block;
+
+    quote! {
+        #(#fn_attrs)*
+        #fn_vis #fn_sig {
+            #prelude
+            #fn_block
+        }
+    }
+    .into()
+}
+
+/// Removes `Inject<T>` and `#[inject(...)]` parameters from `sig` and returns the
+/// statement binding them from `DIScope::current()`; empty when nothing is injected.
+pub(crate) fn take_injected_params(sig: &mut Signature) -> syn::Result<TokenStream2> {
+    let mut patterns = Vec::new();
+    let mut types = Vec::new();
+    let mut values = Vec::new();
+    let mut remaining = Punctuated::<FnArg, Token![,]>::new();
+
+    for input in std::mem::take(&mut sig.inputs) {
+        let FnArg::Typed(mut param) = input else {
+            remaining.push(input);
+            continue;
+        };
+        match injected_value(&param)? {
+            Some(value) => {
+                param.attrs.retain(|attr| !is_inject_attr(attr));
+                patterns.push(param.pat);
+                types.push(param.ty);
+                values.push(value);
+            }
+            None => remaining.push(FnArg::Typed(param)),
+        }
+    }
+    sig.inputs = remaining;
+
+    if values.is_empty() {
+        return Ok(quote!());
+    }
+
+    let fn_name = sig.ident.to_string();
+    let on_error = if returns_result(&sig.output) {
+        quote!(return ::std::result::Result::Err(::std::convert::From::from(err)))
+    } else {
+        // Без `Result` помилку нікуди повернути
+        quote!(panic!("failed to inject dependencies of `{}`: {}", #fn_name, err))
+    };
+
+    Ok(quote! {
+        let (#(#patterns,)*): (#(#types,)*) = match async {
+            let scope = ::rust_di::DIScope::current()?;
+            ::std::result::Result::Ok::<_, ::rust_di::DiError>((#(#values,)*))
+        }
+        .await
+        {
+            ::std::result::Result::Ok(values) => values,
+            ::std::result::Result::Err(err) => #on_error,
+        };
+    })
+}
+
+/// `None` for parameters left to the caller.
+fn injected_value(param: &PatType) -> syn::Result<Option<TokenStream2>> {
+    let annotated = param.attrs.iter().any(is_inject_attr);
+    let inject_of = generic_argument(&param.ty, "Inject");
+    if !annotated && inject_of.is_none() {
+        return Ok(None);
+    }
+
+    let args = parse_inject_args(&param.attrs)?;
+    if let Some(inner) = inject_of
+        && !args.default
+        && args.with.is_none()
+    {
+        let source = if args.open {
+            InjectSource::Open(inner.clone())
+        } else {
+            InjectSource::Required(inner.clone())
+        };
+        let value = resolve_expr(&source, args.name.as_ref());
+        return Ok(Some(quote!(::rust_di::Inject::new(#value))));
+    }
+
+    let source = inject_source(&param.ty, &args)?;
+    Ok(Some(resolve_expr(&source, args.name.as_ref())))
+}
+
+fn returns_result(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(type_path) => type_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Result"),
+            _ => false,
+        },
+        ReturnType::Default => false,
+    }
+}
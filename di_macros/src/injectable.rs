@@ -0,0 +1,67 @@
+use crate::injection::{inject_source, parse_inject_args, resolve_expr};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, parse_macro_input};
+
+pub(crate) fn derive_injectable(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    match expand(&input) {
+        Ok(expanded) => expanded.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "`Injectable` can only be derived for structs",
+        ));
+    };
+
+    let construct = match &data.fields {
+        Fields::Named(fields) => {
+            let values = fields
+                .named
+                .iter()
+                .map(|field| {
+                    let args = parse_inject_args(&field.attrs)?;
+                    let source = inject_source(&field.ty, &args)?;
+                    let value = resolve_expr(&source, args.name.as_ref());
+                    let field_ident = &field.ident;
+                    Ok(quote!(#field_ident: #value))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote!(Self { #(#values),* })
+        }
+        Fields::Unnamed(fields) => {
+            let values = fields
+                .unnamed
+                .iter()
+                .map(|field| {
+                    let args = parse_inject_args(&field.attrs)?;
+                    let source = inject_source(&field.ty, &args)?;
+                    Ok(resolve_expr(&source, args.name.as_ref()))
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote!(Self(#(#values),*))
+        }
+        Fields::Unit => quote!(Self),
+    };
+
+    Ok(quote! {
+        #[::rust_di::async_trait::async_trait]
+        impl #impl_generics ::rust_di::core::factory::DiFactory for #ident #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            async fn create(
+                scope: ::std::sync::Arc<::rust_di::DIScope>,
+            ) -> ::std::result::Result<Self, ::rust_di::DiError> {
+                Ok(#construct)
+            }
+        }
+    })
+}
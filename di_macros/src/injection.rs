@@ -0,0 +1,145 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Attribute, Expr, GenericArgument, LitStr, PathArguments, Type};
+
+/// How a single field or parameter gets its value.
+pub(crate) enum InjectSource {
+    /// `Arc<T>` — resolved from the scope, missing service is an error.
+    Required(Type),
+    /// `Option<Arc<T>>` — resolved from the scope, `None` when not registered.
+    Optional(Type),
+    /// `#[inject(open)] Arc<T>` — member of an open-generic family.
+    Open(Type),
+    /// `#[inject(default)]`
+    Default,
+    /// `#[inject(with = expr)]`
+    With(Expr),
+}
+
+/// Parsed `#[inject(...)]` options.
+#[derive(Default)]
+pub(crate) struct InjectArgs {
+    pub name: Option<LitStr>,
+    pub default: bool,
+    pub with: Option<Expr>,
+    pub open: bool,
+}
+
+pub(crate) fn is_inject_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("inject")
+}
+
+pub(crate) fn parse_inject_args(attrs: &[Attribute]) -> syn::Result<InjectArgs> {
+    let mut args = InjectArgs::default();
+
+    for attr in attrs.iter().filter(|attr| is_inject_attr(attr)) {
+        // `#[inject]` без аргументів
+        if matches!(attr.meta, syn::Meta::Path(_)) {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                args.name = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                args.default = true;
+            } else if meta.path.is_ident("with") {
+                args.with = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("open") {
+                args.open = true;
+            } else {
+                return Err(meta.error(
+                    "unknown `inject` option, expected `name = \"...\"`, `open`, `default` or `with = ...`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    if args.default && args.with.is_some() {
+        return Err(syn::Error::new(
+            attrs
+                .iter()
+                .find(|attr| is_inject_attr(attr))
+                .map(|attr| attr.span())
+                .unwrap_or_else(proc_macro2::Span::call_site),
+            "`default` and `with` cannot be combined",
+        ));
+    }
+
+    Ok(args)
+}
+
+/// Decides how a value of type `ty` is produced.
+pub(crate) fn inject_source(ty: &Type, args: &InjectArgs) -> syn::Result<InjectSource> {
+    if let Some(with) = &args.with {
+        return Ok(InjectSource::With(with.clone()));
+    }
+    if args.default {
+        return Ok(InjectSource::Default);
+    }
+    if let Some(inner) = generic_argument(ty, "Arc") {
+        if args.open {
+            return Ok(InjectSource::Open(inner.clone()));
+        }
+        return Ok(InjectSource::Required(inner.clone()));
+    }
+    if args.open {
+        return Err(syn::Error::new_spanned(
+            ty,
+            "`#[inject(open)]` requires an `Arc<T>` field",
+        ));
+    }
+    if let Some(inner) = generic_argument(ty, "Option").and_then(|ty| generic_argument(ty, "Arc")) {
+        return Ok(InjectSource::Optional(inner.clone()));
+    }
+
+    Err(syn::Error::new_spanned(
+        ty,
+        "cannot inject this type: expected `Arc<T>` or `Option<Arc<T>>`, \
+         or mark it with `#[inject(default)]` / `#[inject(with = ...)]`",
+    ))
+}
+
+/// Expression producing the value, evaluated inside an `async` block that
+/// returns `Result<_, DiError>` and has `scope: Arc<DIScope>` in scope.
+pub(crate) fn resolve_expr(source: &InjectSource, name: Option<&LitStr>) -> TokenStream2 {
+    let name = name.map(|name| quote!(#name)).unwrap_or_else(|| quote!(""));
+
+    match source {
+        InjectSource::Required(ty) => quote! {
+            scope.clone().get_by_name::<#ty>(#name).await?
+        },
+        InjectSource::Optional(ty) => quote! {
+            scope.clone().try_get_by_name::<#ty>(#name).await?
+        },
+        InjectSource::Open(ty) => quote! {
+            scope.clone().get_open_by_name::<#ty>(#name).await?
+        },
+        InjectSource::Default => quote! {
+            ::std::default::Default::default()
+        },
+        InjectSource::With(expr) => quote! {
+            #expr
+        },
+    }
+}
+
+/// Returns `T` for `Wrapper<T>` when the last path segment is `wrapper`.
+pub(crate) fn generic_argument<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) if arguments.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
@@ -1,7 +1,12 @@
 extern crate proc_macro;
 
+mod factory_macro;
+mod inject_macro;
+mod injectable;
+mod injection;
 mod main_macro;
 mod register_macros;
+mod test_macro;
 mod with_di_scope;
 
 use proc_macro::TokenStream;
@@ -16,7 +21,27 @@ pub fn registry(attr: TokenStream, item: TokenStream) -> TokenStream {
     register_macros::generate_di_macro(attr, item)
 }
 
+#[proc_macro_attribute]
+pub fn factory(attr: TokenStream, item: TokenStream) -> TokenStream {
+    factory_macro::expand_factory(attr, item)
+}
+
+#[proc_macro_attribute]
+pub fn inject(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    inject_macro::expand_inject(item)
+}
+
 #[proc_macro_attribute]
 pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
     main_macro::expand_main(attr, item)
 }
+
+#[proc_macro_attribute]
+pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    test_macro::expand_test(attr, item)
+}
+
+#[proc_macro_derive(Injectable, attributes(inject))]
+pub fn derive_injectable(item: TokenStream) -> TokenStream {
+    injectable::derive_injectable(item)
+}
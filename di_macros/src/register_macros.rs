@@ -1,136 +1,385 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use std::hash::Hash;
-use syn::parse::Parser;
+use quote::{ToTokens, quote};
+use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
-use syn::{Expr, ItemImpl, Lit, Meta, Path, Token, parse_macro_input};
+use syn::spanned::Spanned;
+use syn::{
+    Expr, ExprLit, ItemImpl, Lit, Meta, MetaList, Path, Token, Type, bracketed, parse_macro_input,
+};
 
-#[derive(Hash)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) enum DiKind {
     Singleton,
     Scoped,
     Transient,
 }
 
+impl DiKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            DiKind::Singleton => "Singleton",
+            DiKind::Scoped => "Scoped",
+            DiKind::Transient => "Transient",
+        }
+    }
+}
+
 pub(crate) struct DiRegistration {
     pub kind: DiKind,
+    /// Span of the `Singleton` / `Scoped` / `Transient` token.
+    pub span: proc_macro2::Span,
     pub use_factory: bool,
     pub factory_path: Option<Path>,
     pub name: Option<String>,
+    pub eager: bool,
+    /// `if_missing` — keep an existing registration of the same key.
+    pub if_missing: bool,
+    /// `timeout = "5s"`, as milliseconds.
+    pub timeout: Option<u64>,
+    /// `retry(attempts = 5, ...)`, as a `RetryPolicy` expression.
+    pub retry: Option<proc_macro2::TokenStream>,
+    /// `fallback = InMemoryCache` or `fallback = "memory"`.
+    pub fallback: Option<Fallback>,
+}
+
+/// What a failing registration is resolved from instead.
+pub(crate) enum Fallback {
+    /// Another registered service, converted with `From<Arc<Service>>`.
+    Service(Path),
+    /// The same service type registered under this name.
+    Name(syn::LitStr),
+}
+
+/// Builder calls of the `Registration`: `.if_missing()`, `.with_timeout(...)`,
+/// `.with_retry(...)`, `.with_fallback_to::<...>()` / `.with_fallback_name(...)`.
+pub(crate) fn registration_options(
+    if_missing: bool,
+    timeout: Option<u64>,
+    retry: Option<&proc_macro2::TokenStream>,
+    fallback: Option<&Fallback>,
+) -> proc_macro2::TokenStream {
+    let if_missing = if_missing.then(|| quote!(.if_missing()));
+    let timeout =
+        timeout.map(|millis| quote!(.with_timeout(::std::time::Duration::from_millis(#millis))));
+    let retry = retry.map(|policy| quote!(.with_retry(#policy)));
+    let fallback = fallback.map(|fallback| match fallback {
+        Fallback::Service(path) => quote!(.with_fallback_to::<#path>()),
+        Fallback::Name(name) => quote!(.with_fallback_name(#name)),
+    });
+    quote!(#if_missing #timeout #retry #fallback)
+}
+
+/// `retry(attempts = 5, backoff = "100ms", max_backoff = "5s", multiplier = 2.0,
+/// jitter = 0.2, cooldown = "30s")`; only `attempts` is required.
+pub(crate) fn parse_retry(list: &MetaList) -> syn::Result<proc_macro2::TokenStream> {
+    let mut attempts = None;
+    let mut options = Vec::new();
+    let mut seen = Vec::new();
+
+    let nested = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+    for meta in nested {
+        let Meta::NameValue(nv) = meta else {
+            return Err(syn::Error::new_spanned(
+                meta,
+                "expected `name = value`, like `retry(attempts = 5, backoff = \"100ms\")`",
+            ));
+        };
+        let option = nv.path.to_token_stream().to_string();
+        if seen.contains(&option) {
+            return Err(syn::Error::new_spanned(
+                &nv.path,
+                format!("duplicate `{option}` option"),
+            ));
+        }
+        seen.push(option.clone());
+
+        match option.as_str() {
+            "attempts" => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Int(lit), ..
+                }) => attempts = Some(lit.base10_parse::<u32>()?),
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "expected an integer, like `attempts = 5`",
+                    ));
+                }
+            },
+            "backoff" | "max_backoff" | "cooldown" => {
+                let millis = parse_timeout(&nv.value)?;
+                let method = match option.as_str() {
+                    "backoff" => quote!(backoff),
+                    "max_backoff" => quote!(max_backoff),
+                    _ => quote!(cache_failure_for),
+                };
+                options.push(quote!(.#method(::std::time::Duration::from_millis(#millis))));
+            }
+            "multiplier" | "jitter" => {
+                let value: f64 = match &nv.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Float(lit),
+                        ..
+                    }) => lit.base10_parse()?,
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(lit), ..
+                    }) => lit.base10_parse()?,
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            format!("expected a number, like `{option} = 0.5`"),
+                        ));
+                    }
+                };
+                let method = syn::Ident::new(&option, nv.path.span());
+                options.push(quote!(.#method(#value)));
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &nv.path,
+                    format!(
+                        "unknown option `{option}`, expected `attempts`, `backoff`, `max_backoff`, \
+                         `multiplier`, `jitter` or `cooldown`"
+                    ),
+                ));
+            }
+        }
+    }
+
+    let attempts = attempts.ok_or_else(|| {
+        syn::Error::new_spanned(list, "missing `attempts`, like `retry(attempts = 5)`")
+    })?;
+    Ok(quote! {
+        ::rust_di::core::retry::RetryPolicy::new(#attempts) #(#options)*
+    })
+}
+
+/// `"500ms"`, `"5s"`, `"2m"` or `"1h"`.
+pub(crate) fn parse_timeout(value: &Expr) -> syn::Result<u64> {
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(lit_str),
+        ..
+    }) = value
+    else {
+        return Err(syn::Error::new_spanned(
+            value,
+            "expected a string literal, like `timeout = \"5s\"`",
+        ));
+    };
+    let text = lit_str.value();
+    let digits = text.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier = match &text[digits.len()..] {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => 0,
+    };
+    let millis = digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|amount| amount.checked_mul(multiplier));
+    match millis {
+        Some(millis) if millis > 0 => Ok(millis),
+        _ => Err(syn::Error::new_spanned(
+            lit_str,
+            "expected a positive duration in `ms`, `s`, `m` or `h`, like `timeout = \"5s\"`",
+        )),
+    }
+}
+
+/// `priority = N` and `after = Type` — where the item's registrations go in `initialize()`.
+#[derive(Default)]
+pub(crate) struct Ordering {
+    pub priority: Option<syn::LitInt>,
+    pub after: Option<Type>,
+}
+
+pub(crate) struct RegistryArgs {
+    pub registrations: Vec<DiRegistration>,
+    pub ordering: Ordering,
+    /// `for = [Repo<User>, Repo<Order>]` — concrete types of a generic impl.
+    pub instantiations: Option<Vec<Type>>,
+    /// `open` — one registration for every instantiation of a generic impl.
+    pub open: bool,
+}
+
+enum RegistryArg {
+    Meta(Box<Meta>),
+    For(Token![for], Vec<Type>),
+    After(syn::Ident, Box<Type>),
+}
+
+impl Parse for RegistryArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fork = input.fork();
+        if fork
+            .parse::<syn::Ident>()
+            .is_ok_and(|ident| ident == "after")
+            && fork.peek(Token![=])
+        {
+            let ident = input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            Ok(RegistryArg::After(ident, Box::new(input.parse()?)))
+        } else if input.peek(Token![for]) {
+            let for_token = input.parse::<Token![for]>()?;
+            input.parse::<Token![=]>()?;
+            let content;
+            bracketed!(content in input);
+            let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+            Ok(RegistryArg::For(for_token, types.into_iter().collect()))
+        } else {
+            input.parse().map(|meta| RegistryArg::Meta(Box::new(meta)))
+        }
+    }
 }
 
 pub(crate) fn generate_di_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let registrations = parse_registry_args(attr);
     let input = parse_macro_input!(item as ItemImpl);
-    let self_ty = &input.self_ty;
+    let args = match parse_registry_args(attr) {
+        Ok(args) => args,
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote! {
+                #input
+                #err
+            }
+            .into();
+        }
+    };
+
+    if args.open {
+        let expanded =
+            generate_open_registrations(&input, &args).unwrap_or_else(|err| err.to_compile_error());
+        return quote! {
+            #input
+            #expanded
+        }
+        .into();
+    }
+
+    let targets = match registration_targets(&input, args.instantiations) {
+        Ok(targets) => targets,
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote! {
+                #input
+                #err
+            }
+            .into();
+        }
+    };
 
     let mut submissions = Vec::new();
 
-    for reg in registrations {
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        let type_name = quote!(#self_ty).to_string();
-        type_name.hash(&mut hasher);
-        reg.name.hash(&mut hasher);
-        reg.use_factory.hash(&mut hasher);
-        reg.kind.hash(&mut hasher);
+    for self_ty in &targets {
+        for reg in &args.registrations {
+            let name_literal = syn::LitStr::new(
+                reg.name.as_deref().unwrap_or(""),
+                proc_macro2::Span::call_site(),
+            );
 
-        let name_literal = syn::LitStr::new(
-            reg.name.as_deref().unwrap_or(""),
-            proc_macro2::Span::call_site(),
-        );
+            let register_singleton = if reg.eager {
+                quote!(register_singleton_name_eager)
+            } else {
+                quote!(register_singleton_name)
+            };
 
-        let registration = match reg.kind {
-            DiKind::Singleton => {
-                if reg.use_factory {
-                    if let Some(factory_path) = &reg.factory_path {
-                        quote! {
-                            ::rust_di::core::registry::register_singleton_name::<#self_ty, _, _>(#name_literal, |scope| async move {
-                                #factory_path::create(scope).await
-                            }).await
+            let options = registration_options(
+                reg.if_missing,
+                reg.timeout,
+                reg.retry.as_ref(),
+                reg.fallback.as_ref(),
+            );
+
+            let registration = match reg.kind {
+                DiKind::Singleton => {
+                    if reg.use_factory {
+                        if let Some(factory_path) = &reg.factory_path {
+                            quote! {
+                                ::rust_di::core::registry::#register_singleton::<#self_ty, _, _>(#name_literal, |scope| async move {
+                                    #factory_path::create(scope).await
+                                }) #options
+                            }
+                        } else {
+                            quote! {
+                                ::rust_di::core::registry::#register_singleton::<#self_ty, _, _>(#name_literal, |scope| async move {
+                                    <#self_ty as ::rust_di::core::factory::DiFactory>::create(scope).await
+                                }) #options
+                            }
                         }
                     } else {
                         quote! {
-                            ::rust_di::core::registry::register_singleton_name::<#self_ty, _, _>(#name_literal, |scope| async move {
-                                <#self_ty as ::rust_di::core::factory::DiFactory>::create(scope).await
-                            }).await
+                            ::rust_di::core::registry::#register_singleton::<#self_ty, _, _>(#name_literal, |_scope| async move {
+                                Ok(<#self_ty as ::rust_di::core::factory::DefaultConstructible>::construct())
+                            }) #options
                         }
                     }
-                } else {
-                    quote! {
-                        ::rust_di::core::registry::register_singleton_name::<#self_ty, _, _>(#name_literal, |_scope| async move {
-                            Ok(<#self_ty as ::std::default::Default>::default())
-                        }).await
-                    }
                 }
-            }
-            DiKind::Scoped => {
-                let factory = if reg.use_factory {
-                    if let Some(factory_path) = &reg.factory_path {
-                        quote! {
-                            let instance = #factory_path::create(scope).await
-                                .map_err(|e| ::rust_di::DiError::FactoryError(Box::new(e)))?;
-                            Ok(instance)
+                DiKind::Scoped => {
+                    let factory = if reg.use_factory {
+                        if let Some(factory_path) = &reg.factory_path {
+                            quote! {
+                                let instance = #factory_path::create(scope).await
+                                    .map_err(|e| ::rust_di::DiError::FactoryError(Box::new(e)))?;
+                                Ok(instance)
+                            }
+                        } else {
+                            quote! {
+                                let instance = <#self_ty as ::rust_di::core::factory::DiFactory>::create(scope).await
+                                    .map_err(|e| ::rust_di::DiError::FactoryError(Box::new(e)))?;
+                                Ok(instance)
+                            }
                         }
                     } else {
                         quote! {
-                            let instance = <#self_ty as ::rust_di::core::factory::DiFactory>::create(scope).await
-                                .map_err(|e| ::rust_di::DiError::FactoryError(Box::new(e)))?;
-                            Ok(instance)
+                            Ok(<#self_ty as ::rust_di::core::factory::DefaultConstructible>::construct())
                         }
-                    }
-                } else {
+                    };
+
                     quote! {
-                        Ok(<#self_ty as ::std::default::Default>::default())
+                        ::rust_di::core::registry::register_scope_name::<#self_ty, _, _>(
+                            #name_literal,
+                            |scope| Box::pin(async move { #factory })
+                        ) #options
                     }
-                };
-
-                quote! {
-                    ::rust_di::core::registry::register_scope_name::<#self_ty, _, _>(
-                        #name_literal,
-                        |scope| Box::pin(async move { #factory })
-                    ).await
                 }
-            }
-            DiKind::Transient => {
-                let factory = if reg.use_factory {
-                    if let Some(factory_path) = &reg.factory_path {
-                        quote! {
-                            let instance = #factory_path::create(scope).await
-                                .map_err(|e| ::rust_di::DiError::FactoryError(Box::new(e)))?;
-                            Ok(instance)
+                DiKind::Transient => {
+                    let factory = if reg.use_factory {
+                        if let Some(factory_path) = &reg.factory_path {
+                            quote! {
+                                let instance = #factory_path::create(scope).await
+                                    .map_err(|e| ::rust_di::DiError::FactoryError(Box::new(e)))?;
+                                Ok(instance)
+                            }
+                        } else {
+                            quote! {
+                                let instance = <#self_ty as ::rust_di::core::factory::DiFactory>::create(scope).await
+                                    .map_err(|e| ::rust_di::DiError::FactoryError(Box::new(e)))?;
+                                Ok(instance)
+                            }
                         }
                     } else {
                         quote! {
-                            let instance = <#self_ty as ::rust_di::core::factory::DiFactory>::create(scope).await
-                                .map_err(|e| ::rust_di::DiError::FactoryError(Box::new(e)))?;
-                            Ok(instance)
+                            Ok(<#self_ty as ::rust_di::core::factory::DefaultConstructible>::construct())
                         }
-                    }
-                } else {
+                    };
+
                     quote! {
-                        Ok(<#self_ty as ::std::default::Default>::default())
+                        ::rust_di::core::registry::register_transient_name::<#self_ty, _, _>(
+                            #name_literal,
+                            |scope| Box::pin(async move { #factory })
+                        ) #options
                     }
-                };
-
-                quote! {
-                    ::rust_di::core::registry::register_transient_name::<#self_ty, _, _>(
-                        #name_literal,
-                        |scope| Box::pin(async move { #factory })
-                    ).await
                 }
-            }
-        };
+            };
 
-        submissions.push(quote! {
-            ::rust_di::inventory::submit! {
-                ::rust_di::core::di_inventory::DiConstructor {
-                    init: || Box::pin(async move {
-                        let scope = ::rust_di::DIScope::new().await;
-                        let _ = #registration;
-                    })
-                }
-            }
-        });
+            submissions.push(constructor(
+                quote!(|batch| batch.add(#registration)),
+                self_ty,
+                &name_literal,
+                &args.ordering,
+            ));
+        }
     }
 
     let expanded = quote! {
@@ -141,86 +390,511 @@ pub(crate) fn generate_di_macro(attr: TokenStream, item: TokenStream) -> TokenSt
     TokenStream::from(expanded)
 }
 
-fn parse_registry_args(attr: TokenStream) -> Vec<DiRegistration> {
-    let metas = Punctuated::<Meta, Token![,]>::parse_terminated
-        .parse(attr.into())
-        .expect("Failed to parse registry attribute");
+/// Types to register: the impl's own type, or the `for = [...]` list of a generic impl.
+fn registration_targets(
+    input: &ItemImpl,
+    instantiations: Option<Vec<Type>>,
+) -> syn::Result<Vec<Type>> {
+    let self_ty = &*input.self_ty;
+    let is_generic = !input.generics.params.is_empty();
 
-    let mut registrations = Vec::new();
+    let Some(instantiations) = instantiations else {
+        if is_generic {
+            return Err(syn::Error::new_spanned(
+                &input.generics,
+                format!(
+                    "generic impl blocks cannot be registered directly; list the concrete types with \
+                     `for = [...]`, e.g. `#[registry(Singleton, for = [{}<MyType>])]`",
+                    type_head(self_ty)
+                        .map(|ident| ident.to_string())
+                        .unwrap_or_default()
+                ),
+            ));
+        }
+        return Ok(vec![self_ty.clone()]);
+    };
 
-    for meta in metas {
-        match meta {
-            Meta::Path(path) => {
-                if let Some(ident) = path.get_ident() {
-                    let kind = match ident.to_string().as_str() {
-                        "Singleton" => DiKind::Singleton,
-                        "Scoped" => DiKind::Scoped,
-                        "Transient" => DiKind::Transient,
-                        _ => continue,
-                    };
+    if !is_generic {
+        return Err(syn::Error::new_spanned(
+            self_ty,
+            "`for = [...]` is only allowed on generic impl blocks",
+        ));
+    }
+    if instantiations.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`for = [...]` must list at least one concrete type",
+        ));
+    }
+    for ty in &instantiations {
+        if type_head(ty) != type_head(self_ty) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!("expected an instantiation of `{}`", quote!(#self_ty)),
+            ));
+        }
+    }
 
-                    registrations.push(DiRegistration {
-                        kind,
-                        use_factory: false,
-                        factory_path: None,
-                        name: None,
-                    });
+    Ok(instantiations)
+}
+
+/// `#[registry(Singleton, open)] impl<T: Entity> Repo<T> {}` registers the `Repo`
+/// family once; members are built through `DiFactory` by `DIScope::get_open`.
+fn generate_open_registrations(
+    input: &ItemImpl,
+    args: &RegistryArgs,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let self_ty = &*input.self_ty;
+
+    if input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            self_ty,
+            "`open` is only allowed on generic impl blocks",
+        ));
+    }
+    if args.instantiations.is_some() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`open` and `for = [...]` cannot be combined",
+        ));
+    }
+    if args.registrations.iter().any(|reg| {
+        reg.factory_path.is_some()
+            || reg.eager
+            || reg.if_missing
+            || reg.timeout.is_some()
+            || reg.retry.is_some()
+            || reg.fallback.is_some()
+    }) {
+        return Err(syn::Error::new_spanned(
+            self_ty,
+            "open registrations support neither `factory = Path`, `eager`, `if_missing`, `timeout`, `retry` nor `fallback`; \
+             implement `DiFactory` for the generic type and use `factory`",
+        ));
+    }
+
+    let use_factory = args.registrations.iter().all(|reg| reg.use_factory);
+    if !use_factory && args.registrations.iter().any(|reg| reg.use_factory) {
+        return Err(syn::Error::new_spanned(
+            self_ty,
+            "open registrations must either all use `factory` or all use `Default`",
+        ));
+    }
+
+    // Без `factory` члени сімейства створюються через `Default`
+    let default_factory = if use_factory {
+        quote!()
+    } else {
+        let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+        let mut predicates = where_clause
+            .map(|clause| clause.predicates.iter().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        predicates.push(syn::parse_quote!(Self: ::std::default::Default));
+        quote! {
+            #[::rust_di::async_trait::async_trait]
+            impl #impl_generics ::rust_di::core::factory::DiFactory for #self_ty
+            where #(#predicates),*
+            {
+                async fn create(
+                    _scope: ::std::sync::Arc<::rust_di::DIScope>,
+                ) -> ::std::result::Result<Self, ::rust_di::DiError> {
+                    Ok(<Self as ::std::default::Default>::default())
                 }
             }
-            Meta::List(list) => {
-                let kind = match list.path.get_ident() {
-                    Some(ident) if ident == "Singleton" => DiKind::Singleton,
-                    Some(ident) if ident == "Scoped" => DiKind::Scoped,
-                    Some(ident) if ident == "Transient" => DiKind::Transient,
-                    _ => continue,
-                };
+        }
+    };
 
-                let mut use_factory = false;
-                let mut factory_path = None;
-                let mut name = None;
+    let marker = family_marker(self_ty)?;
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+    let family = quote! {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        pub struct #marker;
 
-                let nested = Punctuated::<Meta, Token![,]>::parse_terminated
-                    .parse2(list.tokens.clone())
-                    .expect("Failed to parse nested meta");
+        impl #impl_generics ::rust_di::core::open_generic::OpenGeneric for #self_ty #where_clause {
+            type Family = #marker;
+        }
+    };
+    let witness: Type = syn::parse_quote!(#marker);
+    let submissions = args.registrations.iter().map(|reg| {
+        let name_literal = syn::LitStr::new(
+            reg.name.as_deref().unwrap_or(""),
+            proc_macro2::Span::call_site(),
+        );
+        let lifetime = match reg.kind {
+            DiKind::Singleton => quote!(Singleton),
+            DiKind::Scoped => quote!(Scoped),
+            DiKind::Transient => quote!(Transient),
+        };
+        constructor(
+            quote! {
+                |batch| batch.add_open::<#witness>(
+                    #name_literal,
+                    ::rust_di::core::contracts::Lifetime::#lifetime,
+                )
+            },
+            &witness,
+            &name_literal,
+            &args.ordering,
+        )
+    });
 
-                for meta in nested {
-                    match meta {
-                        Meta::Path(p) if p.is_ident("factory") => {
-                            use_factory = true;
-                            factory_path = None;
-                        }
-                        Meta::NameValue(nv) if nv.path.is_ident("factory") => {
-                            use_factory = true;
-
-                            if let Expr::Path(expr_path) = nv.value {
-                                factory_path = Some(expr_path.path);
-                            } else {
-                                panic!(
-                                    "Expected a path for `factory = ...`, like `factory = MyFactory`"
-                                );
-                            }
-                        }
-                        Meta::NameValue(nv) if nv.path.is_ident("name") => {
-                            if let Expr::Lit(expr_lit) = nv.value {
-                                if let Lit::Str(lit_str) = expr_lit.lit {
-                                    name = Some(lit_str.value());
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
+    Ok(quote! {
+        #default_factory
+        #family
+        #(#submissions)*
+    })
+}
+
+/// The family token of `Repo<T>`: a unit struct `__RepoOpenFamily` next to the impl,
+/// well-formed whatever bounds `Repo` puts on `T`.
+fn family_marker(self_ty: &Type) -> syn::Result<syn::Ident> {
+    let head = type_head(self_ty).ok_or_else(|| {
+        syn::Error::new_spanned(self_ty, "`open` requires a path type like `Repo<T>`")
+    })?;
+    Ok(quote::format_ident!("__{}OpenFamily", head))
+}
+
+fn type_head(ty: &Type) -> Option<&syn::Ident> {
+    match ty {
+        Type::Path(type_path) => type_path.path.segments.last().map(|segment| &segment.ident),
+        _ => None,
+    }
+}
+
+fn parse_registry_args(attr: TokenStream) -> syn::Result<RegistryArgs> {
+    let args = Punctuated::<RegistryArg, Token![,]>::parse_terminated.parse(attr)?;
+
+    let mut registrations: Vec<DiRegistration> = Vec::new();
+    let mut instantiations = None;
+    let mut open = false;
+    let mut ordering = Ordering::default();
+
+    for arg in args {
+        let meta = match arg {
+            RegistryArg::Meta(meta) => *meta,
+            RegistryArg::After(ident, ty) => {
+                if ordering.after.is_some() {
+                    return Err(syn::Error::new_spanned(ident, "duplicate `after` option"));
+                }
+                ordering.after = Some(*ty);
+                continue;
+            }
+            RegistryArg::For(for_token, types) => {
+                if instantiations.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        for_token,
+                        "duplicate `for = [...]` option",
+                    ));
+                }
+                instantiations = Some(types);
+                continue;
+            }
+        };
+
+        let registration = match meta {
+            Meta::Path(path) if path.is_ident("open") => {
+                if open {
+                    return Err(syn::Error::new_spanned(path, "duplicate `open` option"));
+                }
+                open = true;
+                continue;
+            }
+            Meta::Path(path) => DiRegistration {
+                kind: parse_kind(&path)?,
+                span: path.span(),
+                use_factory: false,
+                factory_path: None,
+                name: None,
+                eager: false,
+                if_missing: false,
+                timeout: None,
+                retry: None,
+                fallback: None,
+            },
+            Meta::List(list) => parse_registration(&list)?,
+            Meta::NameValue(nv) if nv.path.is_ident("priority") => {
+                if ordering.priority.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &nv.path,
+                        "duplicate `priority` option",
+                    ));
+                }
+                ordering.priority = Some(parse_priority(&nv.value)?);
+                continue;
+            }
+            Meta::NameValue(nv) => {
+                return Err(syn::Error::new_spanned(
+                    &nv.path,
+                    format!(
+                        "unknown option `{}`, expected `Singleton`, `Scoped`, `Transient`, `open`, \
+                         `for = [...]`, `priority = N` or `after = Type`",
+                        nv.path.to_token_stream()
+                    ),
+                ));
+            }
+        };
+
+        // Той самий name вдруге — дублікат або конфлікт lifetime у runtime
+        if let Some(existing) = registrations
+            .iter()
+            .find(|reg| reg.name == registration.name)
+        {
+            let named = registration
+                .name
+                .as_deref()
+                .map(|name| format!(" named \"{name}\""))
+                .unwrap_or_default();
+            let message = if existing.kind == registration.kind {
+                format!(
+                    "duplicate `{}` registration{named}",
+                    registration.kind.as_str()
+                )
+            } else {
+                format!(
+                    "conflicting lifetimes: the service{named} is already registered as `{}`; \
+                     use a different `name` for the `{}` registration",
+                    existing.kind.as_str(),
+                    registration.kind.as_str()
+                )
+            };
+            let mut err = syn::Error::new(registration.span, message);
+            err.combine(syn::Error::new(existing.span, "first registered here"));
+            return Err(err);
+        }
+        registrations.push(registration);
+    }
+
+    if registrations.is_empty() {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "expected at least one registration: `Singleton`, `Scoped` or `Transient`",
+        ));
+    }
+
+    Ok(RegistryArgs {
+        registrations,
+        ordering,
+        instantiations,
+        open,
+    })
+}
+
+/// `priority = 10` or `priority = -5`.
+fn parse_priority(value: &Expr) -> syn::Result<syn::LitInt> {
+    let error = || syn::Error::new_spanned(value, "expected an integer, like `priority = 10`");
+    let (negative, lit) = match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => (false, lit),
+        Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) => (true, lit),
+            _ => return Err(error()),
+        },
+        _ => return Err(error()),
+    };
+    let priority: i32 = lit.base10_parse()?;
+    let priority = if negative { -priority } else { priority };
+    Ok(syn::LitInt::new(&priority.to_string(), lit.span()))
+}
+
+/// The `DiConstructor` submitted for one registration of `service`.
+pub(crate) fn constructor(
+    register: proc_macro2::TokenStream,
+    service: &Type,
+    name: &syn::LitStr,
+    ordering: &Ordering,
+) -> proc_macro2::TokenStream {
+    let priority = ordering
+        .priority
+        .as_ref()
+        .map(|priority| quote!(#priority))
+        .unwrap_or_else(|| quote!(0));
+    let after = match &ordering.after {
+        Some(after) => quote!(::std::option::Option::Some(::std::any::TypeId::of::<#after>)),
+        None => quote!(::std::option::Option::None),
+    };
+    quote! {
+        ::rust_di::inventory::submit! {
+            ::rust_di::core::di_inventory::DiConstructor {
+                register: #register,
+                priority: #priority,
+                service: ::std::any::TypeId::of::<#service>,
+                service_name: ::std::any::type_name::<#service>,
+                name: #name,
+                location: ::std::concat!(::std::file!(), ":", ::std::line!(), ":", ::std::column!()),
+                after: #after,
+            }
+        }
+    }
+}
+
+fn parse_kind(path: &Path) -> syn::Result<DiKind> {
+    match path.get_ident().map(|ident| ident.to_string()).as_deref() {
+        Some("Singleton") => Ok(DiKind::Singleton),
+        Some("Scoped") => Ok(DiKind::Scoped),
+        Some("Transient") => Ok(DiKind::Transient),
+        _ => Err(syn::Error::new_spanned(
+            path,
+            format!(
+                "unknown registration kind `{}`, expected `Singleton`, `Scoped` or `Transient`",
+                path.to_token_stream()
+            ),
+        )),
+    }
+}
+
+/// `Singleton(factory = MyFactory, name = "primary", eager, if_missing, timeout = "5s", retry(...),
+/// fallback = InMemoryCache)`
+fn parse_registration(list: &MetaList) -> syn::Result<DiRegistration> {
+    let kind = parse_kind(&list.path)?;
+
+    let mut use_factory = false;
+    let mut factory_path = None;
+    let mut name = None;
+    let mut eager = false;
+    let mut if_missing = false;
+    let mut timeout = None;
+    let mut retry = None;
+    let mut fallback = None;
+
+    let nested = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+    for meta in nested {
+        match meta {
+            Meta::Path(path) if path.is_ident("factory") => {
+                if use_factory {
+                    return Err(syn::Error::new_spanned(path, "duplicate `factory` option"));
+                }
+                use_factory = true;
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("factory") => {
+                if use_factory {
+                    return Err(syn::Error::new_spanned(
+                        &nv.path,
+                        "duplicate `factory` option",
+                    ));
                 }
+                use_factory = true;
 
-                registrations.push(DiRegistration {
-                    kind,
-                    use_factory,
-                    factory_path,
-                    name,
+                let Expr::Path(expr_path) = nv.value else {
+                    return Err(syn::Error::new_spanned(
+                        nv.value,
+                        "expected a path for `factory = ...`, like `factory = MyFactory`",
+                    ));
+                };
+                factory_path = Some(expr_path.path);
+            }
+            Meta::Path(path) if path.is_ident("eager") => {
+                if eager {
+                    return Err(syn::Error::new_spanned(path, "duplicate `eager` option"));
+                }
+                if kind != DiKind::Singleton {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "`eager` is only supported for `Singleton` registrations",
+                    ));
+                }
+                eager = true;
+            }
+            Meta::Path(path) if path.is_ident("if_missing") => {
+                if if_missing {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "duplicate `if_missing` option",
+                    ));
+                }
+                if_missing = true;
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("name") => {
+                if name.is_some() {
+                    return Err(syn::Error::new_spanned(&nv.path, "duplicate `name` option"));
+                }
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit_str),
+                    ..
+                }) = nv.value
+                else {
+                    return Err(syn::Error::new_spanned(
+                        nv.value,
+                        "expected a string literal, like `name = \"primary\"`",
+                    ));
+                };
+                name = Some(lit_str.value());
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("timeout") => {
+                if timeout.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &nv.path,
+                        "duplicate `timeout` option",
+                    ));
+                }
+                timeout = Some(parse_timeout(&nv.value)?);
+            }
+            Meta::List(list) if list.path.is_ident("retry") => {
+                if retry.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &list.path,
+                        "duplicate `retry` option",
+                    ));
+                }
+                if kind != DiKind::Singleton {
+                    return Err(syn::Error::new_spanned(
+                        &list.path,
+                        "`retry` is only supported for `Singleton` registrations",
+                    ));
+                }
+                retry = Some(parse_retry(&list)?);
+            }
+            Meta::NameValue(nv) if nv.path.is_ident("fallback") => {
+                if fallback.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &nv.path,
+                        "duplicate `fallback` option",
+                    ));
+                }
+                fallback = Some(match nv.value {
+                    Expr::Path(expr_path) => Fallback::Service(expr_path.path),
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(name),
+                        ..
+                    }) => Fallback::Name(name),
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected a service or a name for `fallback = ...`, like `fallback = InMemoryCache` or `fallback = \"memory\"`",
+                        ));
+                    }
                 });
             }
-            _ => {}
+            other => {
+                let path = other.path();
+                return Err(syn::Error::new_spanned(
+                    path,
+                    format!(
+                        "unknown option `{}`, expected `factory`, `factory = Path`, `name = \"...\"`, `eager`, `if_missing`, `timeout = \"...\"`, `retry(...)` or `fallback = ...`",
+                        path.to_token_stream()
+                    ),
+                ));
+            }
         }
     }
 
-    registrations
+    Ok(DiRegistration {
+        kind,
+        span: list.path.span(),
+        use_factory,
+        factory_path,
+        name,
+        eager,
+        if_missing,
+        timeout,
+        retry,
+        fallback,
+    })
 }
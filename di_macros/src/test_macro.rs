@@ -0,0 +1,96 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Expr, ItemFn, LitInt, LitStr, Path, parse_macro_input};
+
+#[derive(Default)]
+struct TestArgs {
+    setups: Vec<Path>,
+    flavor: Option<LitStr>,
+    worker_threads: Option<LitInt>,
+}
+
+pub(crate) fn expand_test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input_fn = parse_macro_input!(item as ItemFn);
+
+    let mut args = TestArgs::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("setup") {
+            match meta.value()?.parse::<Expr>()? {
+                Expr::Path(expr_path) => args.setups.push(expr_path.path),
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "expected a path to an async fn, like `setup = fake_mailer`",
+                    ));
+                }
+            }
+        } else if meta.path.is_ident("flavor") {
+            args.flavor = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("worker_threads") {
+            args.worker_threads = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error(
+                "unknown option, expected `setup = path`, `flavor = \"...\"` or `worker_threads = N`",
+            ));
+        }
+        Ok(())
+    });
+    parse_macro_input!(attr with parser);
+
+    let sig = &input_fn.sig;
+    if sig.asyncness.is_none() {
+        return syn::Error::new_spanned(
+            sig.fn_token,
+            "`#[rust_di::test]` can only be applied to async functions",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if !sig.inputs.is_empty() {
+        return syn::Error::new_spanned(
+            &sig.inputs,
+            "`#[rust_di::test]` functions cannot take arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let flavor = args.flavor.map(|flavor| quote!(, flavor = #flavor));
+    let worker_threads = args
+        .worker_threads
+        .map(|threads| quote!(, worker_threads = #threads));
+    let setups = args.setups.iter().map(|setup| {
+        let setup_name = quote!(#setup).to_string();
+        quote! {
+            if let ::std::result::Result::Err(err) = #setup().await {
+                panic!("rust_di test setup `{}` failed: {}", #setup_name, err);
+            }
+        }
+    });
+
+    let attrs = &input_fn.attrs;
+    let vis = &input_fn.vis;
+    let block = &input_fn.block;
+
+    quote! {
+        #(#attrs)*
+        #[::rust_di::tokio::test(crate = "::rust_di::tokio" #flavor #worker_threads)]
+        #vis #sig {
+            let __rust_di_container = ::rust_di::core::container::Container::new();
+            let __rust_di_output = __rust_di_container
+                .clone()
+                .run(async {
+                    // Спершу overrides, потім inventory: дублікати з inventory ігноруються
+                    #(#setups)*
+                    if let ::std::result::Result::Err(err) = __rust_di_container.initialize().await {
+                        panic!("rust_di initialization failed: {}", err);
+                    }
+                    ::rust_di::DIScope::run_with_scope(|| async #block).await
+                })
+                .await;
+            __rust_di_container.clear();
+            __rust_di_output
+        }
+    }
+    .into()
+}
@@ -1,9 +1,16 @@
+use crate::inject_macro::take_injected_params;
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{ItemFn, parse_macro_input};
 
 pub(crate) fn with_di_scope(item: TokenStream) -> TokenStream {
-    let input_fn = parse_macro_input!(item as ItemFn);
+    let mut input_fn = parse_macro_input!(item as ItemFn);
+
+    // `Inject<T>` параметри резолвляться вже всередині нового scope
+    let prelude = match take_injected_params(&mut input_fn.sig) {
+        Ok(prelude) => prelude,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
     let fn_name = &input_fn.sig.ident;
     let fn_inputs = &input_fn.sig.inputs;
@@ -26,6 +33,7 @@ pub(crate) fn with_di_scope(item: TokenStream) -> TokenStream {
         #(#fn_attrs)*
         #fn_vis #fn_async fn #fn_name(#fn_inputs) #fn_output {
             rust_di::DIScope::run_with_scope(|| async {
+                #prelude
                 #fn_block
             }).await
         }
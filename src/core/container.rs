@@ -0,0 +1,902 @@
+use crate::DIScope;
+use crate::core::context::{ContextKey, Scoped, context_local};
+use crate::core::contracts::{
+    DuplicatePolicy, Lifetime, LifetimeConflictPolicy, LocalFactory, RegistrationMap,
+    ServiceFactory, ServiceInstance, ServiceKey,
+};
+use crate::core::di_inventory::{DiConstructor, RegistrationBatch};
+use crate::core::error_di::DiError;
+use crate::core::open_generic::TypeFamily;
+use crate::core::registry::{PendingRegistration, with_fallback, with_timeout, wrap_factory};
+use crate::core::retry::with_retry;
+use arc_swap::ArcSwap;
+use dashmap::DashMap;
+use futures::lock::{Mutex as AsyncMutex, OwnedMutexGuard};
+use futures::{StreamExt, TryStreamExt, stream};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Order in which `DIScope` looks a key up when it is registered with several lifetimes.
+pub(crate) const RESOLUTION_ORDER: [Lifetime; 3] =
+    [Lifetime::Scoped, Lifetime::Singleton, Lifetime::Transient];
+
+const DEFAULT_INIT_CONCURRENCY: usize = 16;
+
+static GLOBAL_CONTAINER: Lazy<Arc<Container>> = Lazy::new(|| Arc::new(Container::empty()));
+
+context_local! {
+    static CURRENT_CONTAINER: Arc<Container>;
+}
+
+/// Registrations, the Singleton cache and the eager list of one application.
+///
+/// Everything uses the global container unless a future runs inside
+/// [`Container::run`]; `#[rust_di::test]` gives every test its own one.
+pub struct Container {
+    /// Every registration, copy-on-write: resolving is a single lock-free lookup,
+    /// registering clones the table.
+    table: ArcSwap<RegistrationMap>,
+    pub(crate) singleton_cache: DashMap<ServiceKey, ServiceInstance>,
+    /// One lock per Singleton under construction, so that concurrent `get`s build it once.
+    singleton_locks: DashMap<ServiceKey, Arc<AsyncMutex<()>>>,
+    singleton_waits: Mutex<SingletonWaits>,
+    pub(crate) eager_singletons: Mutex<Vec<ServiceKey>>,
+    /// Open-generic registrations: `(family, name)` -> lifetime of every instantiation.
+    pub(crate) open_generic_families: DashMap<(TypeFamily, String), Lifetime>,
+    /// Factories of `!Send` services, resolved through a `LocalScope`.
+    pub(crate) local_factories: DashMap<ServiceKey, (Lifetime, LocalFactory)>,
+    /// Number of `DuplicatePolicy::Append` registrations stored next to each key.
+    pub(crate) appended: DashMap<ServiceKey, usize>,
+    /// Why each service constructed by its fallback has its primary factory failed.
+    pub(crate) fallbacks: DashMap<ServiceKey, String>,
+    /// Active `replace` overrides of each key, restored in any drop order.
+    overrides: DashMap<ServiceKey, OverrideStack>,
+    next_override: AtomicU64,
+    duplicate_policy: Mutex<DuplicatePolicy>,
+    lifetime_conflict_policy: Mutex<LifetimeConflictPolicy>,
+    factory_timeout: Mutex<Option<Duration>>,
+    /// Серіалізує реєстрації: перевірка конфліктів і вставка атомарні
+    registration_lock: Mutex<()>,
+    /// Bumped by `clear`, so `initialize` runs again for the new generation.
+    generation: AtomicU64,
+    /// Generation the inventory registrations ran for, and their first lifetime conflict.
+    initialized: AsyncMutex<Option<(u64, Option<LifetimeConflict>)>>,
+    /// Generation the eager Singletons were constructed for.
+    warmed_up: AsyncMutex<Option<u64>>,
+    init_concurrency: AtomicUsize,
+}
+
+impl fmt::Debug for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let table = self.table.load();
+        let count = |lifetime| {
+            table
+                .values()
+                .filter(|registered| registered.get(lifetime).is_some())
+                .count()
+        };
+        f.debug_struct("Container")
+            .field("singletons", &count(Lifetime::Singleton))
+            .field("scoped", &count(Lifetime::Scoped))
+            .field("transients", &count(Lifetime::Transient))
+            .field("cached_singletons", &self.singleton_cache.len())
+            .finish()
+    }
+}
+
+impl Container {
+    fn empty() -> Self {
+        Self {
+            table: ArcSwap::from_pointee(RegistrationMap::default()),
+            singleton_cache: DashMap::new(),
+            singleton_locks: DashMap::new(),
+            singleton_waits: Mutex::default(),
+            eager_singletons: Mutex::new(Vec::new()),
+            open_generic_families: DashMap::new(),
+            local_factories: DashMap::new(),
+            appended: DashMap::new(),
+            fallbacks: DashMap::new(),
+            overrides: DashMap::new(),
+            next_override: AtomicU64::new(0),
+            duplicate_policy: Mutex::new(DuplicatePolicy::Error),
+            lifetime_conflict_policy: Mutex::new(LifetimeConflictPolicy::Error),
+            factory_timeout: Mutex::new(None),
+            registration_lock: Mutex::new(()),
+            generation: AtomicU64::new(0),
+            initialized: AsyncMutex::new(None),
+            warmed_up: AsyncMutex::new(None),
+            init_concurrency: AtomicUsize::new(DEFAULT_INIT_CONCURRENCY),
+        }
+    }
+
+    /// Creates an empty container, independent of the global one.
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::empty())
+    }
+
+    pub fn global() -> Arc<Self> {
+        GLOBAL_CONTAINER.clone()
+    }
+
+    /// The container of the enclosing [`Container::run`], or the global one.
+    pub fn current() -> Arc<Self> {
+        CURRENT_CONTAINER
+            .with_current(|container| container.clone())
+            .unwrap_or_else(|_| Self::global())
+    }
+
+    /// Runs `fut` with this container as [`Container::current`]: registrations,
+    /// `initialize()` and new scopes inside it use this container.
+    pub async fn run<F>(self: Arc<Self>, fut: F) -> F::Output
+    where
+        F: Future,
+    {
+        self.scope(fut).await
+    }
+
+    pub(crate) fn scope<F: Future>(self: Arc<Self>, fut: F) -> Scoped<Arc<Self>, F> {
+        CURRENT_CONTAINER.scope_future(self, fut)
+    }
+
+    pub(crate) fn run_sync<R>(self: Arc<Self>, f: impl FnOnce() -> R) -> R {
+        CURRENT_CONTAINER.scope_sync(self, f)
+    }
+
+    /// Registers every `#[registry]` service once and constructs the eager Singletons.
+    ///
+    /// Services registered before the first call win over inventory registrations
+    /// of the same key, which is how `#[rust_di::test(setup = ...)]` overrides them.
+    /// A service registered with two lifetimes fails with `DiError::LifetimeConflict`
+    /// (unless the lifetime conflict policy says otherwise), on every call.
+    /// After [`Container::clear`] the next call registers and warms everything up again.
+    pub async fn initialize(self: &Arc<Self>) -> Result<(), DiError> {
+        let generation = self.generation.load(Ordering::Acquire);
+        let conflict = {
+            let mut initialized = self.initialized.lock().await;
+            match initialized.as_ref() {
+                Some((done, conflict)) if *done == generation => conflict.clone(),
+                _ => {
+                    let mut batch = RegistrationBatch::default();
+                    for ctor in DiConstructor::ordered() {
+                        (ctor.register)(&mut batch);
+                    }
+                    let conflict = self.apply_batch(batch)?;
+                    *initialized = Some((generation, conflict.clone()));
+                    conflict
+                }
+            }
+        };
+        if let Some(conflict) = conflict {
+            return Err(DiError::LifetimeConflict(conflict));
+        }
+
+        let mut warmed_up = self.warmed_up.lock().await;
+        if *warmed_up != Some(generation) {
+            self.warm_up().await?;
+            *warmed_up = Some(generation);
+        }
+        Ok(())
+    }
+
+    /// Applies the inventory registrations and returns their first lifetime conflict.
+    fn apply_batch(&self, batch: RegistrationBatch) -> Result<Option<LifetimeConflict>, DiError> {
+        for (family, name, lifetime) in &batch.open_families {
+            // Дублікати з inventory ігноруються
+            let _ = self.insert_open_family(family, name, *lifetime);
+        }
+        let outcomes = self.apply_registrations(batch.services)?;
+        Ok(outcomes.into_iter().find_map(|outcome| match outcome {
+            Err(DiError::LifetimeConflict(conflict)) => Some(conflict),
+            _ => None,
+        }))
+    }
+
+    /// Constructs every eager Singleton that is not cached yet, each inside its
+    /// own scope, at most [`Container::init_concurrency`] at a time.
+    pub async fn warm_up(self: &Arc<Self>) -> Result<(), DiError> {
+        let keys = self
+            .eager_singletons
+            .lock()
+            .map_err(|_| DiError::LockPoisoned)?
+            .clone();
+
+        let constructions = stream::iter(keys.into_iter().map(|key| {
+            DIScope::run_with_scope(move || async move {
+                DIScope::current()?.resolve(key).await.map(|_| ())
+            })
+        }))
+        .buffer_unordered(self.init_concurrency())
+        .try_collect::<Vec<()>>();
+        self.clone().run(constructions).await?;
+
+        Ok(())
+    }
+
+    /// Takes the lock held while the Singleton `key` is constructed by the
+    /// resolution chain `resolution`.
+    ///
+    /// Fails with `CircularDependency` instead of waiting when the chain holding
+    /// the lock waits, directly or through other chains, for `resolution`.
+    pub(crate) async fn lock_singleton(
+        &self,
+        key: ServiceKey,
+        resolution: u64,
+    ) -> Result<SingletonBuild<'_>, DiError> {
+        let lock = self.singleton_locks.entry(key).or_default().clone();
+        let guard = match lock.try_lock_owned() {
+            Some(guard) => guard,
+            None => {
+                let waiting = {
+                    let mut waits = self
+                        .singleton_waits
+                        .lock()
+                        .map_err(|_| DiError::LockPoisoned)?;
+                    if waits.closes_cycle(key, resolution) {
+                        return Err(DiError::CircularDependency(key.name().to_string()));
+                    }
+                    waits.waiting.insert(resolution, key);
+                    Waiting {
+                        container: self,
+                        resolution,
+                    }
+                };
+                let guard = lock.lock_owned().await;
+                drop(waiting);
+                guard
+            }
+        };
+        self.singleton_waits
+            .lock()
+            .map_err(|_| DiError::LockPoisoned)?
+            .holders
+            .insert(key, resolution);
+        Ok(SingletonBuild {
+            container: self,
+            key,
+            resolution,
+            _guard: guard,
+        })
+    }
+
+    /// How many eager Singletons `warm_up` constructs at once; 16 by default.
+    pub fn init_concurrency(&self) -> usize {
+        self.init_concurrency.load(Ordering::Relaxed)
+    }
+
+    /// Sets the [`Container::init_concurrency`] limit; `0` is treated as `1`.
+    pub fn set_init_concurrency(&self, limit: usize) {
+        self.init_concurrency.store(limit.max(1), Ordering::Relaxed);
+    }
+
+    /// Removes every registration and drops the cached Singletons.
+    ///
+    /// The next [`Container::initialize`] registers the `#[registry]` services again.
+    pub fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.table.store(Arc::new(RegistrationMap::default()));
+        self.singleton_cache.clear();
+        self.singleton_locks.clear();
+        if let Ok(mut waits) = self.singleton_waits.lock() {
+            *waits = SingletonWaits::default();
+        }
+        self.open_generic_families.clear();
+        self.local_factories.clear();
+        self.appended.clear();
+        self.fallbacks.clear();
+        self.overrides.clear();
+        if let Ok(mut eager) = self.eager_singletons.lock() {
+            eager.clear();
+        }
+    }
+
+    /// Policy of registrations that don't set their own; `Error` by default.
+    pub fn duplicate_policy(&self) -> DuplicatePolicy {
+        self.duplicate_policy
+            .lock()
+            .map(|policy| *policy)
+            .unwrap_or_default()
+    }
+
+    pub fn set_duplicate_policy(&self, policy: DuplicatePolicy) {
+        if let Ok(mut current) = self.duplicate_policy.lock() {
+            *current = policy;
+        }
+    }
+
+    /// Policy for registering a key that already has another lifetime; `Error` by default.
+    pub fn lifetime_conflict_policy(&self) -> LifetimeConflictPolicy {
+        self.lifetime_conflict_policy
+            .lock()
+            .map(|policy| *policy)
+            .unwrap_or_default()
+    }
+
+    pub fn set_lifetime_conflict_policy(&self, policy: LifetimeConflictPolicy) {
+        if let Ok(mut current) = self.lifetime_conflict_policy.lock() {
+            *current = policy;
+        }
+    }
+
+    /// Timeout of registrations that don't set their own; none by default.
+    pub fn factory_timeout(&self) -> Option<Duration> {
+        self.factory_timeout
+            .lock()
+            .map(|timeout| *timeout)
+            .unwrap_or_default()
+    }
+
+    /// Applies to registrations made afterwards, so set it before `initialize()`.
+    pub fn set_factory_timeout(&self, timeout: Option<Duration>) {
+        if let Ok(mut current) = self.factory_timeout.lock() {
+            *current = timeout;
+        }
+    }
+
+    /// Stores `factory` under `key` according to the policies; returns the key it
+    /// was stored under, or `None` when an existing registration was kept.
+    pub(crate) fn insert_factory(
+        &self,
+        lifetime: Lifetime,
+        key: ServiceKey,
+        factory: ServiceFactory,
+        policy: DuplicatePolicy,
+        conflict_policy: LifetimeConflictPolicy,
+    ) -> Result<Option<ServiceKey>, DiError> {
+        let _registering = self
+            .registration_lock
+            .lock()
+            .map_err(|_| DiError::LockPoisoned)?;
+        let mut table = RegistrationMap::clone(&self.table.load());
+        let mut replaced = Vec::new();
+        let stored = self.insert_into(
+            &mut table,
+            &mut replaced,
+            lifetime,
+            key,
+            factory,
+            policy,
+            conflict_policy,
+        );
+        self.table.store(Arc::new(table));
+        self.evict(&replaced);
+        stored
+    }
+
+    /// Applies `registrations` in order on a single copy of the table and returns
+    /// whether each one was stored. Eager Singletons are queued for `warm_up`.
+    pub(crate) fn apply_registrations(
+        &self,
+        registrations: Vec<PendingRegistration>,
+    ) -> Result<Vec<Result<bool, DiError>>, DiError> {
+        let duplicate_policy = self.duplicate_policy();
+        let lifetime_conflict_policy = self.lifetime_conflict_policy();
+        let factory_timeout = self.factory_timeout();
+        let _registering = self
+            .registration_lock
+            .lock()
+            .map_err(|_| DiError::LockPoisoned)?;
+        let mut table = RegistrationMap::clone(&self.table.load());
+        let mut replaced = Vec::new();
+
+        let mut eager = Vec::new();
+        let outcomes = registrations
+            .into_iter()
+            .map(|registration| {
+                if registration.retry.is_some() && registration.lifetime != Lifetime::Singleton {
+                    return Err(DiError::InvalidRegistration {
+                        key: registration.key,
+                        reason: "`with_retry` is only supported for Singletons",
+                    });
+                }
+                let mut factory = registration.factory;
+                if let Some(timeout) = registration.timeout.or(factory_timeout) {
+                    factory = with_timeout(factory, registration.key, timeout);
+                }
+                // Кожна спроба має власний timeout
+                if let Some(policy) = registration.retry {
+                    factory = with_retry(factory, registration.key, policy);
+                }
+                if let Some(mut fallback) = registration.fallback {
+                    if let Some(timeout) = registration.timeout.or(factory_timeout) {
+                        fallback = with_timeout(fallback, registration.key, timeout);
+                    }
+                    factory = with_fallback(factory, fallback, registration.key);
+                }
+                let stored = self.insert_into(
+                    &mut table,
+                    &mut replaced,
+                    registration.lifetime,
+                    registration.key,
+                    factory,
+                    registration.policy.unwrap_or(duplicate_policy),
+                    registration
+                        .lifetime_conflict
+                        .unwrap_or(lifetime_conflict_policy),
+                )?;
+                if registration.eager
+                    && let Some(key) = stored
+                {
+                    eager.push(key);
+                }
+                Ok(stored.is_some())
+            })
+            .collect();
+
+        self.table.store(Arc::new(table));
+        self.evict(&replaced);
+        self.eager_singletons
+            .lock()
+            .map_err(|_| DiError::LockPoisoned)?
+            .extend(eager);
+        Ok(outcomes)
+    }
+
+    /// Registers the open-generic `family` under `name`.
+    pub(crate) fn insert_open_family(
+        &self,
+        family: &TypeFamily,
+        name: &str,
+        lifetime: Lifetime,
+    ) -> Result<(), DiError> {
+        use dashmap::mapref::entry::Entry;
+        match self
+            .open_generic_families
+            .entry((*family, name.to_string()))
+        {
+            Entry::Occupied(_) => Err(DiError::ServiceAlreadyRegistered(name.to_string())),
+            Entry::Vacant(entry) => {
+                entry.insert(lifetime);
+                Ok(())
+            }
+        }
+    }
+
+    /// Stores `factory` in `table`; keys whose factory was replaced or removed are
+    /// pushed to `replaced`, to be evicted once the table is stored.
+    #[allow(clippy::too_many_arguments)]
+    fn insert_into(
+        &self,
+        table: &mut RegistrationMap,
+        replaced: &mut Vec<ServiceKey>,
+        lifetime: Lifetime,
+        key: ServiceKey,
+        factory: ServiceFactory,
+        policy: DuplicatePolicy,
+        conflict_policy: LifetimeConflictPolicy,
+    ) -> Result<Option<ServiceKey>, DiError> {
+        let other_lifetimes: Vec<Lifetime> = table
+            .get(&key)
+            .map(|registered| {
+                registered
+                    .lifetimes()
+                    .filter(|other| *other != lifetime)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(&existing) = other_lifetimes.first() {
+            match conflict_policy {
+                LifetimeConflictPolicy::Error => {
+                    return Err(DiError::LifetimeConflict(LifetimeConflict {
+                        service: key.type_name().to_string(),
+                        name: key.name().to_string(),
+                        existing,
+                        requested: lifetime,
+                    }));
+                }
+                LifetimeConflictPolicy::KeepExisting => return Ok(None),
+                LifetimeConflictPolicy::Replace => {
+                    for other in other_lifetimes {
+                        for binding in self.binding_keys(&key) {
+                            Self::remove_factory(table, other, &binding);
+                            replaced.push(binding);
+                        }
+                    }
+                    self.appended.remove(&key);
+                }
+                LifetimeConflictPolicy::Allow => {}
+            }
+        }
+
+        let slot = table.entry(key).or_default().slot(lifetime);
+        if slot.is_none() {
+            *slot = Some(factory);
+            Ok(Some(key))
+        } else {
+            match policy {
+                DuplicatePolicy::Error => {
+                    Err(DiError::ServiceAlreadyRegistered(key.name().to_string()))
+                }
+                DuplicatePolicy::KeepFirst => Ok(None),
+                DuplicatePolicy::Replace => {
+                    *slot = Some(factory);
+                    replaced.push(key);
+                    Ok(Some(key))
+                }
+                DuplicatePolicy::Append => {
+                    let index = {
+                        let mut count = self.appended.entry(key).or_insert(0);
+                        *count += 1;
+                        *count
+                    };
+                    let appended_key = key.with_binding(index);
+                    *table.entry(appended_key).or_default().slot(lifetime) = Some(factory);
+                    Ok(Some(appended_key))
+                }
+            }
+        }
+    }
+
+    /// Keys of every registration of `key`: the key itself, then the appended ones.
+    pub(crate) fn binding_keys(&self, key: &ServiceKey) -> Vec<ServiceKey> {
+        let appended = self.appended.get(key).map(|count| *count).unwrap_or(0);
+        std::iter::once(*key)
+            .chain((1..=appended).map(|index| key.with_binding(index)))
+            .collect()
+    }
+
+    /// Atomically replaces the registration of `T` under `name` (adding it if missing)
+    /// and evicts its cached Singleton. The guard restores the previous registration on drop.
+    ///
+    /// The override hides every lifetime `T` was registered with, so `get` always
+    /// resolves it with `lifetime`. Overrides of one key may be dropped in any order;
+    /// the container's [`Container::factory_timeout`] applies to them as well.
+    ///
+    /// Scoped instances already created in live scopes are kept.
+    pub fn replace<T, F, Fut>(
+        self: &Arc<Self>,
+        lifetime: Lifetime,
+        name: &str,
+        factory: F,
+    ) -> OverrideGuard
+    where
+        T: Send + Sync + 'static,
+        F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+    {
+        let key = ServiceKey::of::<T>(name);
+        let mut factory = wrap_factory(factory);
+        if let Some(timeout) = self.factory_timeout() {
+            factory = with_timeout(factory, key, timeout);
+        }
+        let mut registered = Registered::default();
+        *registered.slot(lifetime) = Some(factory);
+        let id = self.next_override.fetch_add(1, Ordering::Relaxed);
+
+        let _registering = self.registration_lock.lock();
+        self.overrides
+            .entry(key)
+            .or_insert_with(|| OverrideStack {
+                base: self.table.load().get(&key).cloned(),
+                layers: Vec::new(),
+            })
+            .layers
+            .push((id, registered.clone()));
+        self.install(&key, Some(registered));
+
+        OverrideGuard {
+            container: self.clone(),
+            lifetime,
+            key,
+            id,
+            restore: true,
+        }
+    }
+
+    /// Ends the override `id` of `key`: the next active override or the original
+    /// registration is installed again. With `persist` the override replaces the original.
+    fn end_override(&self, key: &ServiceKey, id: u64, persist: bool) {
+        let _registering = self.registration_lock.lock();
+        let Some(mut stack) = self.overrides.get_mut(key) else {
+            return;
+        };
+        let Some(position) = stack.layers.iter().position(|(layer, _)| *layer == id) else {
+            return;
+        };
+        let (_, registered) = stack.layers.remove(position);
+        if persist {
+            stack.base = Some(registered);
+        }
+        let installed = match stack.layers.last() {
+            Some((_, top)) => Some(top.clone()),
+            None => stack.base.clone(),
+        };
+        let finished = stack.layers.is_empty();
+        drop(stack);
+        if finished {
+            self.overrides.remove(key);
+        }
+        self.install(key, installed);
+    }
+
+    /// Copy-on-write заміна, щоб читачі бачили або стару, або нову таблицю
+    fn install(&self, key: &ServiceKey, registered: Option<Registered>) {
+        let mut table = RegistrationMap::clone(&self.table.load());
+        match registered {
+            Some(registered) => table.insert(*key, registered),
+            None => table.remove(key),
+        };
+        // Спершу нова таблиця, потім очищення кешу: див. `DIScope::resolve`
+        self.table.store(Arc::new(table));
+        self.evict(&[*key]);
+    }
+
+    fn remove_factory(table: &mut RegistrationMap, lifetime: Lifetime, key: &ServiceKey) {
+        if let Some(registered) = table.get_mut(key) {
+            registered.slot(lifetime).take();
+            if registered.lifetimes().next().is_none() {
+                table.remove(key);
+            }
+        }
+    }
+
+    /// Drops what was built by replaced factories. Runs after the new table is
+    /// stored, so that a construction in flight cannot cache a stale instance.
+    fn evict(&self, keys: &[ServiceKey]) {
+        for key in keys {
+            self.singleton_cache.remove(key);
+            self.fallbacks.remove(key);
+        }
+    }
+
+    /// Whether `factory` is still the one `DIScope` resolves `key` with.
+    pub(crate) fn is_current(&self, key: &ServiceKey, factory: &ServiceFactory) -> bool {
+        self.table
+            .load()
+            .get(key)
+            .and_then(Registered::resolved)
+            .is_some_and(|(_, current)| Arc::ptr_eq(current, factory))
+    }
+
+    /// The lifetime and factory `DIScope` resolves `key` with.
+    pub(crate) fn lookup(&self, key: &ServiceKey) -> Option<(Lifetime, ServiceFactory)> {
+        let table = self.table.load();
+        let (lifetime, factory) = table.get(key)?.resolved()?;
+        Some((lifetime, factory.clone()))
+    }
+
+    pub(crate) fn is_registered(&self, key: &ServiceKey) -> bool {
+        self.table.load().contains_key(key)
+    }
+
+    /// How `T` registered under `name` is resolved, or `None` when it is not registered.
+    pub fn registration<T: ?Sized + 'static>(&self, name: &str) -> Option<RegistrationInfo> {
+        self.registration_of(&ServiceKey::of::<T>(name))
+    }
+
+    /// Every registered service, sorted by type and name.
+    pub fn registrations(&self) -> Vec<RegistrationInfo> {
+        let mut keys: Vec<ServiceKey> = self
+            .table
+            .load()
+            .keys()
+            .filter(|key| key.binding() == 0)
+            .copied()
+            .collect();
+        keys.sort_by(|a, b| (a.type_name(), a.name()).cmp(&(b.type_name(), b.name())));
+        keys.iter()
+            .filter_map(|key| self.registration_of(key))
+            .collect()
+    }
+
+    /// Services whose last construction used their fallback, sorted by type and name.
+    pub fn fallbacks(&self) -> Vec<FallbackInfo> {
+        let mut fallbacks: Vec<FallbackInfo> = self
+            .fallbacks
+            .iter()
+            .map(|entry| FallbackInfo {
+                service: entry.key().type_name().to_string(),
+                name: entry.key().name().to_string(),
+                reason: entry.value().clone(),
+            })
+            .collect();
+        fallbacks.sort_by(|a, b| (&a.service, &a.name).cmp(&(&b.service, &b.name)));
+        fallbacks
+    }
+
+    fn registration_of(&self, key: &ServiceKey) -> Option<RegistrationInfo> {
+        let table = self.table.load();
+        let mut lifetimes = table.get(key)?.lifetimes();
+        let lifetime = lifetimes.next()?;
+        Some(RegistrationInfo {
+            service: key.type_name().to_string(),
+            name: key.name().to_string(),
+            lifetime,
+            shadowed: lifetimes.collect(),
+            bindings: self.binding_keys(key).len(),
+        })
+    }
+}
+
+/// Factories registered for one key, one per lifetime.
+#[derive(Clone, Default)]
+pub(crate) struct Registered {
+    scoped: Option<ServiceFactory>,
+    singleton: Option<ServiceFactory>,
+    transient: Option<ServiceFactory>,
+}
+
+impl Registered {
+    pub(crate) fn get(&self, lifetime: Lifetime) -> Option<&ServiceFactory> {
+        match lifetime {
+            Lifetime::Scoped => self.scoped.as_ref(),
+            Lifetime::Singleton => self.singleton.as_ref(),
+            Lifetime::Transient => self.transient.as_ref(),
+        }
+    }
+
+    fn slot(&mut self, lifetime: Lifetime) -> &mut Option<ServiceFactory> {
+        match lifetime {
+            Lifetime::Scoped => &mut self.scoped,
+            Lifetime::Singleton => &mut self.singleton,
+            Lifetime::Transient => &mut self.transient,
+        }
+    }
+
+    /// Registered lifetimes in [`RESOLUTION_ORDER`].
+    fn lifetimes(&self) -> impl Iterator<Item = Lifetime> + '_ {
+        RESOLUTION_ORDER
+            .into_iter()
+            .filter(|lifetime| self.get(*lifetime).is_some())
+    }
+
+    /// The lifetime `get` uses, with its factory.
+    fn resolved(&self) -> Option<(Lifetime, &ServiceFactory)> {
+        RESOLUTION_ORDER
+            .into_iter()
+            .find_map(|lifetime| self.get(lifetime).map(|factory| (lifetime, factory)))
+    }
+}
+
+/// Which resolution chain builds each Singleton and which Singleton each chain waits for.
+#[derive(Default)]
+struct SingletonWaits {
+    holders: HashMap<ServiceKey, u64>,
+    waiting: HashMap<u64, ServiceKey>,
+}
+
+impl SingletonWaits {
+    /// Whether `resolution` waiting for `key` would wait for itself.
+    fn closes_cycle(&self, key: ServiceKey, resolution: u64) -> bool {
+        let mut current = key;
+        for _ in 0..=self.holders.len() {
+            let Some(&holder) = self.holders.get(&current) else {
+                return false;
+            };
+            if holder == resolution {
+                return true;
+            }
+            let Some(&next) = self.waiting.get(&holder) else {
+                return false;
+            };
+            current = next;
+        }
+        false
+    }
+}
+
+/// Removes the wait of a resolution chain, also when its `get` is abandoned.
+struct Waiting<'a> {
+    container: &'a Container,
+    resolution: u64,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut waits) = self.container.singleton_waits.lock() {
+            waits.waiting.remove(&self.resolution);
+        }
+    }
+}
+
+/// Holds the lock of a Singleton under construction.
+pub(crate) struct SingletonBuild<'a> {
+    container: &'a Container,
+    key: ServiceKey,
+    resolution: u64,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl Drop for SingletonBuild<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut waits) = self.container.singleton_waits.lock()
+            && waits.holders.get(&self.key) == Some(&self.resolution)
+        {
+            waits.holders.remove(&self.key);
+        }
+    }
+}
+
+/// Registration of one key before its first active override, and the overrides on top.
+struct OverrideStack {
+    base: Option<Registered>,
+    /// `(id, registration)` of every active override; the last one is installed.
+    layers: Vec<(u64, Registered)>,
+}
+
+/// Restores the registration replaced by [`Container::replace`] or an
+/// `override_*` function when dropped.
+#[must_use = "the override is reverted as soon as the guard is dropped"]
+pub struct OverrideGuard {
+    container: Arc<Container>,
+    lifetime: Lifetime,
+    key: ServiceKey,
+    id: u64,
+    restore: bool,
+}
+
+impl OverrideGuard {
+    /// Keeps the new factory instead of restoring the previous one.
+    ///
+    /// Overrides that are still active on the same key keep hiding it until they are dropped.
+    pub fn persist(mut self) {
+        self.restore = false;
+        self.container.end_override(&self.key, self.id, true);
+    }
+}
+
+impl fmt::Debug for OverrideGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OverrideGuard")
+            .field("lifetime", &self.lifetime)
+            .field("key", &self.key)
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        if self.restore {
+            self.container.end_override(&self.key, self.id, false);
+        }
+    }
+}
+
+/// A service registered with one lifetime that was registered again with another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifetimeConflict {
+    pub service: String,
+    pub name: String,
+    pub existing: Lifetime,
+    pub requested: Lifetime,
+}
+
+impl fmt::Display for LifetimeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` (name {:?}) is registered as {}, cannot register it as {}",
+            self.service,
+            self.name,
+            self.existing.as_str(),
+            self.requested.as_str()
+        )
+    }
+}
+
+/// A service running on its fallback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackInfo {
+    pub service: String,
+    pub name: String,
+    /// Error of the primary factory.
+    pub reason: String,
+}
+
+/// Introspection of one `(type, name)` registration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistrationInfo {
+    pub service: String,
+    pub name: String,
+    /// The lifetime `get` actually uses.
+    pub lifetime: Lifetime,
+    /// Other lifetimes registered for the same key, hidden by `lifetime`;
+    /// only possible with `LifetimeConflictPolicy::Allow` or overrides.
+    pub shadowed: Vec<Lifetime>,
+    /// Registrations returned by `get_all`, including `DuplicatePolicy::Append` ones.
+    pub bindings: usize,
+}
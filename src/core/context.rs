@@ -0,0 +1,195 @@
+//! Values visible to a future and everything it calls: the current scope,
+//! container and resolving stack.
+//!
+//! With the `tokio` feature they are `tokio::task_local!`s; without it,
+//! [`PollLocal`]s that work on any executor.
+
+use pin_project_lite::pin_project;
+use std::cell::RefCell;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Storage of a value scoped to a future, like `tokio::task::LocalKey`.
+pub(crate) trait ContextKey<T: 'static> {
+    type Scope<F: Future>: Future<Output = F::Output>;
+
+    /// Sets the value while `fut` is polled.
+    fn scope_future<F: Future>(&'static self, value: T, fut: F) -> Self::Scope<F>;
+
+    /// Sets the value while `f` runs.
+    fn scope_sync<F: FnOnce() -> R, R>(&'static self, value: T, f: F) -> R;
+
+    fn with_current<F: FnOnce(&T) -> R, R>(&'static self, f: F) -> Result<R, NotInContext>;
+}
+
+/// The value is not set for the running code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct NotInContext;
+
+impl fmt::Display for NotInContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("task-local value not set")
+    }
+}
+
+#[cfg(feature = "tokio")]
+pub(crate) type Key<T> = tokio::task::LocalKey<T>;
+#[cfg(not(feature = "tokio"))]
+pub(crate) type Key<T> = PollLocal<T>;
+
+/// Future running `F` with a [`context_local!`] value of type `T` set.
+pub(crate) type Scoped<T, F> = <Key<T> as ContextKey<T>>::Scope<F>;
+
+/// Declares statics implementing [`ContextKey`] with the implementation the
+/// features select.
+macro_rules! context_local {
+    ($(static $name:ident: $t:ty;)+) => {
+        #[cfg(feature = "tokio")]
+        ::tokio::task_local! {
+            $(static $name: $t;)+
+        }
+        #[cfg(not(feature = "tokio"))]
+        $crate::core::context::poll_local! {
+            $(static $name: $t;)+
+        }
+    };
+}
+pub(crate) use context_local;
+
+#[cfg_attr(feature = "tokio", allow(unused_macros))]
+macro_rules! poll_local {
+    ($(static $name:ident: $t:ty;)+) => {
+        $(
+            static $name: $crate::core::context::PollLocal<$t> = {
+                ::std::thread_local! {
+                    static SLOT: ::std::cell::RefCell<::std::option::Option<$t>> =
+                        const { ::std::cell::RefCell::new(::std::option::Option::None) };
+                }
+                $crate::core::context::PollLocal::new(&SLOT)
+            };
+        )+
+    };
+}
+#[cfg_attr(feature = "tokio", allow(unused_imports))]
+pub(crate) use poll_local;
+
+#[cfg(feature = "tokio")]
+impl<T: 'static> ContextKey<T> for tokio::task::LocalKey<T> {
+    type Scope<F: Future> = tokio::task::futures::TaskLocalFuture<T, F>;
+
+    fn scope_future<F: Future>(&'static self, value: T, fut: F) -> Self::Scope<F> {
+        self.scope(value, fut)
+    }
+
+    fn scope_sync<F: FnOnce() -> R, R>(&'static self, value: T, f: F) -> R {
+        self.sync_scope(value, f)
+    }
+
+    fn with_current<F: FnOnce(&T) -> R, R>(&'static self, f: F) -> Result<R, NotInContext> {
+        self.try_with(f).map_err(|_| NotInContext)
+    }
+}
+
+/// Runtime-independent [`ContextKey`]: the value is moved into a thread-local
+/// for every poll of the scoped future and moved back afterwards.
+pub(crate) struct PollLocal<T: 'static> {
+    slot: &'static std::thread::LocalKey<RefCell<Option<T>>>,
+}
+
+impl<T: 'static> PollLocal<T> {
+    #[cfg_attr(feature = "tokio", allow(dead_code))]
+    pub(crate) const fn new(slot: &'static std::thread::LocalKey<RefCell<Option<T>>>) -> Self {
+        Self { slot }
+    }
+
+    /// Runs `f` with `value` in the slot, then puts the previous value back.
+    fn enter<R>(&'static self, value: &mut Option<T>, f: impl FnOnce() -> R) -> R {
+        struct Restore<'a, T: 'static> {
+            slot: &'static std::thread::LocalKey<RefCell<Option<T>>>,
+            value: &'a mut Option<T>,
+        }
+
+        impl<T: 'static> Drop for Restore<'_, T> {
+            fn drop(&mut self) {
+                self.slot
+                    .with(|cell| std::mem::swap(&mut *cell.borrow_mut(), self.value));
+            }
+        }
+
+        self.slot
+            .with(|cell| std::mem::swap(&mut *cell.borrow_mut(), value));
+        let _restore = Restore {
+            slot: self.slot,
+            value,
+        };
+        f()
+    }
+}
+
+impl<T: 'static> ContextKey<T> for PollLocal<T> {
+    type Scope<F: Future> = PollScoped<T, F>;
+
+    fn scope_future<F: Future>(&'static self, value: T, fut: F) -> Self::Scope<F> {
+        PollScoped {
+            key: self,
+            value: Some(value),
+            fut: Some(fut),
+        }
+    }
+
+    fn scope_sync<F: FnOnce() -> R, R>(&'static self, value: T, f: F) -> R {
+        self.enter(&mut Some(value), f)
+    }
+
+    fn with_current<F: FnOnce(&T) -> R, R>(&'static self, f: F) -> Result<R, NotInContext> {
+        self.slot
+            .try_with(|cell| cell.borrow().as_ref().map(f))
+            .ok()
+            .flatten()
+            .ok_or(NotInContext)
+    }
+}
+
+pin_project! {
+    /// Future returned by [`PollLocal::scope_future`].
+    pub(crate) struct PollScoped<T: 'static, F> {
+        key: &'static PollLocal<T>,
+        value: Option<T>,
+        #[pin]
+        fut: Option<F>,
+    }
+
+    impl<T: 'static, F> PinnedDrop for PollScoped<T, F> {
+        fn drop(this: Pin<&mut Self>) {
+            let mut this = this.project();
+            if this.fut.is_some() {
+                // Як у tokio: майбутнє знищується всередині свого scope
+                let key = *this.key;
+                key.enter(this.value, || this.fut.set(None));
+            }
+        }
+    }
+}
+
+impl<T: 'static, F: Future> Future for PollScoped<T, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let key = *this.key;
+        key.enter(this.value, || {
+            let fut = this
+                .fut
+                .as_mut()
+                .as_pin_mut()
+                .expect("`PollScoped` polled after completion");
+            let poll = fut.poll(cx);
+            if poll.is_ready() {
+                this.fut.set(None);
+            }
+            poll
+        })
+    }
+}
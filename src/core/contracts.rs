@@ -1,23 +1,196 @@
 use crate::DIScope;
+use crate::core::container::Registered;
 use crate::core::error_di::DiError;
-use arc_swap::ArcSwap;
+use crate::core::local::LocalScope;
 use dashmap::DashMap;
-use std::any::Any;
+use once_cell::sync::Lazy;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::pin::Pin;
+use std::rc::Rc;
 use std::sync::Arc;
-use tokio::sync::OnceCell;
 
 pub trait AnyService: Any + Send + Sync + 'static {}
 impl<T: Any + Send + Sync + 'static> AnyService for T {}
 
 pub(crate) type ServiceInstance = Arc<dyn AnyService + Send + Sync + 'static>;
 pub(crate) type ScopedMap = DashMap<ServiceKey, ServiceInstance>;
-pub(crate) type FactoryMap = DashMap<ServiceKey, ServiceFactory>;
-pub(crate) type ServiceKey = (String, String);
-pub(crate) type RegisteredInstances = OnceCell<ArcSwap<FactoryMap>>;
+pub(crate) type RegistrationMap = HashMap<ServiceKey, Registered>;
 pub(crate) type ServiceFactory = Arc<
     dyn Fn(Arc<DIScope>) -> Pin<Box<dyn Future<Output = Result<ServiceInstance, DiError>> + Send>>
         + Send
         + Sync
         + 'static,
 >;
+
+/// A `!Send` service built by a local factory, see [`LocalScope`].
+pub(crate) type LocalInstance = Rc<dyn Any>;
+pub(crate) type LocalFactory = Arc<
+    dyn Fn(Rc<LocalScope>) -> Pin<Box<dyn Future<Output = Result<LocalInstance, DiError>>>>
+        + Send
+        + Sync
+        + 'static,
+>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lifetime {
+    Singleton,
+    Scoped,
+    Transient,
+}
+
+impl Lifetime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Lifetime::Singleton => "singleton",
+            Lifetime::Scoped => "scoped",
+            Lifetime::Transient => "transient",
+        }
+    }
+}
+
+/// What a registration does when its key is already registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DuplicatePolicy {
+    /// Fail with `DiError::ServiceAlreadyRegistered`.
+    #[default]
+    Error,
+    /// Keep the existing registration and ignore the new one.
+    KeepFirst,
+    /// Replace the existing registration and evict its cached Singleton.
+    Replace,
+    /// Keep both: `get` still returns the first, `get_all` returns every one.
+    Append,
+}
+
+/// What a registration does when its key is already registered with another lifetime.
+///
+/// A key registered with several lifetimes resolves Scoped first, then Singleton,
+/// then Transient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LifetimeConflictPolicy {
+    /// Fail with `DiError::LifetimeConflict`.
+    #[default]
+    Error,
+    /// Keep the existing registration and ignore the new one.
+    KeepExisting,
+    /// Remove the registrations with other lifetimes and register the new one.
+    Replace,
+    /// Keep both; the precedence above decides which one `get` uses.
+    Allow,
+}
+
+/// Identifies a registration: the service type, its name and, for
+/// `DuplicatePolicy::Append`, the position among the bindings of that name.
+///
+/// Equality uses the `TypeId`; the type name is kept for diagnostics only,
+/// since `type_name` is not unique (e.g. two versions of one crate).
+/// Names are interned, so keys are `Copy` and compare names by pointer.
+#[derive(Clone, Copy)]
+pub struct ServiceKey {
+    type_id: TypeId,
+    type_name: &'static str,
+    name: &'static str,
+    binding: usize,
+}
+
+/// Імена реєстрацій живуть до кінця програми: їх скінченна кількість
+static NAMES: Lazy<DashMap<&'static str, ()>> = Lazy::new(DashMap::new);
+/// Одна адреса для `""`: різні літерали можуть мати різні вказівники
+static DEFAULT_NAME: &str = "";
+
+impl ServiceKey {
+    /// Key of a registration, interning `name`.
+    pub(crate) fn of<T: ?Sized + 'static>(name: &str) -> Self {
+        Self::with_name::<T>(Self::interned(name).unwrap_or_else(|| {
+            let leaked: &'static str = Box::leak(name.into());
+            *NAMES.entry(leaked).or_insert(()).key()
+        }))
+    }
+
+    /// Key to look `T` up with; `None` when no registration ever used `name`,
+    /// which also means nothing is registered under it. Doesn't allocate.
+    pub(crate) fn lookup<T: ?Sized + 'static>(name: &str) -> Option<Self> {
+        Self::interned(name).map(Self::with_name::<T>)
+    }
+
+    fn interned(name: &str) -> Option<&'static str> {
+        if name.is_empty() {
+            return Some(DEFAULT_NAME);
+        }
+        NAMES.get(name).map(|entry| *entry.key())
+    }
+
+    fn with_name<T: ?Sized + 'static>(name: &'static str) -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            name,
+            binding: 0,
+        }
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// `0` for the first registration of a key, `1..` for appended ones.
+    pub fn binding(&self) -> usize {
+        self.binding
+    }
+
+    pub(crate) fn with_binding(&self, binding: usize) -> Self {
+        Self { binding, ..*self }
+    }
+}
+
+impl PartialEq for ServiceKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+            && std::ptr::eq(self.name, other.name)
+            && self.binding == other.binding
+    }
+}
+
+impl Eq for ServiceKey {}
+
+impl Hash for ServiceKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_id.hash(state);
+        self.name.as_ptr().hash(state);
+        self.binding.hash(state);
+    }
+}
+
+impl fmt::Display for ServiceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`", self.type_name)?;
+        if !self.name.is_empty() {
+            write!(f, " (name {:?})", self.name)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ServiceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("ServiceKey");
+        debug
+            .field("type", &self.type_name)
+            .field("name", &self.name);
+        if self.binding > 0 {
+            debug.field("binding", &self.binding);
+        }
+        debug.finish()
+    }
+}
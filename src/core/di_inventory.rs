@@ -1,4 +1,106 @@
+use crate::DIScope;
+use crate::core::contracts::Lifetime;
+use crate::core::error_di::DiError;
+use crate::core::open_generic::TypeFamily;
+use crate::core::registry::{PendingRegistration, Registration};
+use std::any::TypeId;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A `#[registry]` or `#[factory]` registration: adds itself to the batch
+/// `initialize()` applies in one pass. Nothing is constructed here.
 #[derive(Debug)]
 pub struct DiConstructor {
-    pub init: fn() -> std::pin::Pin<Box<dyn Future<Output = ()> + Send>>,
+    pub register: fn(&mut RegistrationBatch),
+    /// Higher priorities are applied first; `0` by default.
+    pub priority: i32,
+    /// `TypeId` of the registered service.
+    pub service: fn() -> TypeId,
+    /// `std::any::type_name` of the registered service, used only as a sort key.
+    pub service_name: fn() -> &'static str,
+    pub name: &'static str,
+    /// `file:line:column` of the item, the last tie-breaker.
+    pub location: &'static str,
+    /// Applied after every registration of this service type.
+    pub after: Option<fn() -> TypeId>,
+}
+
+impl DiConstructor {
+    /// Every inventory registration in the order `initialize()` applies them:
+    /// by priority, then by service, name and location, independent of link order.
+    ///
+    /// `after` wins over priority; registrations in an `after` cycle keep the sorted order.
+    pub fn ordered() -> Vec<&'static DiConstructor> {
+        let mut pending: Vec<(&'static DiConstructor, TypeId)> = inventory::iter::<DiConstructor>
+            .into_iter()
+            .map(|ctor| (ctor, (ctor.service)()))
+            .collect();
+        pending.sort_by_key(|(ctor, _)| {
+            (
+                Reverse(ctor.priority),
+                (ctor.service_name)(),
+                ctor.name,
+                ctor.location,
+            )
+        });
+
+        let mut remaining: HashMap<TypeId, usize> = HashMap::new();
+        for (_, service) in &pending {
+            *remaining.entry(*service).or_default() += 1;
+        }
+
+        let mut ordered = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let ready = pending
+                .iter()
+                .position(|(ctor, service)| match ctor.after.map(|after| after()) {
+                    Some(after) if after != *service => {
+                        remaining.get(&after).copied().unwrap_or(0) == 0
+                    }
+                    _ => true,
+                })
+                // Цикл через `after` — беремо перший за сортуванням
+                .unwrap_or(0);
+            let (ctor, service) = pending.remove(ready);
+            if let Some(count) = remaining.get_mut(&service) {
+                *count -= 1;
+            }
+            ordered.push(ctor);
+        }
+        ordered
+    }
+}
+
+/// Registrations collected from the inventory before they are applied.
+#[derive(Default)]
+pub struct RegistrationBatch {
+    pub(crate) services: Vec<PendingRegistration>,
+    pub(crate) open_families: Vec<(TypeFamily, String, Lifetime)>,
+}
+
+impl RegistrationBatch {
+    pub fn add<T, F, Fut>(&mut self, registration: Registration<T, F, Fut>)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+    {
+        self.services.push(registration.into_pending());
+    }
+
+    /// Adds an open-generic registration of the family with the token `W`.
+    pub fn add_open<W: ?Sized + 'static>(&mut self, name: &str, lifetime: Lifetime) {
+        self.open_families
+            .push((TypeFamily::token::<W>(), name.to_string(), lifetime));
+    }
+}
+
+impl std::fmt::Debug for RegistrationBatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegistrationBatch")
+            .field("services", &self.services.len())
+            .field("open_families", &self.open_families.len())
+            .finish()
+    }
 }
@@ -1,5 +1,9 @@
+use crate::core::container::LifetimeConflict;
+use crate::core::contracts::ServiceKey;
 use std::any::Any;
 use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 pub trait AnyError: Error + Any + Send + Sync + 'static {
@@ -29,6 +33,28 @@ pub enum DiError {
     #[error("DiError: Service factory error: {0}")]
     FactoryError(Box<dyn Error + Send + Sync + 'static>),
 
+    #[error("DiError: Conflicting lifetimes: {0}")]
+    LifetimeConflict(LifetimeConflict),
+
+    #[error("DiError: Service cannot be resolved without waiting: {0}")]
+    WouldBlock(String),
+
+    #[error("DiError: Factory of {key} timed out after {elapsed:?}")]
+    FactoryTimeout { key: ServiceKey, elapsed: Duration },
+
+    #[error("DiError: Factory of {key} failed after {attempts} attempt(s): {cause}")]
+    FactoryFailed {
+        key: ServiceKey,
+        attempts: u32,
+        cause: Arc<DiError>,
+    },
+
+    #[error("DiError: Invalid registration of {key}: {reason}")]
+    InvalidRegistration {
+        key: ServiceKey,
+        reason: &'static str,
+    },
+
     #[error("DiError: Circular dependency detected for with name: {0}")]
     CircularDependency(String),
 
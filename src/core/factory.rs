@@ -8,3 +8,20 @@ pub trait DiFactory: Send + Sync + 'static {
     where
         Self: Sized;
 }
+
+/// Constructs services registered without `factory`; reports a readable error
+/// when the type does not implement `Default`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is registered without a factory, so it must implement `Default`",
+    label = "`{Self}` does not implement `Default`",
+    note = "derive or implement `Default`, or register it with `factory` / `factory = MyFactory`"
+)]
+pub trait DefaultConstructible: Sized {
+    fn construct() -> Self;
+}
+
+impl<T: Default> DefaultConstructible for T {
+    fn construct() -> Self {
+        T::default()
+    }
+}
@@ -0,0 +1,50 @@
+use crate::core::container::Container;
+use crate::core::context::Scoped;
+use crate::{DIScope, ResolvingStack};
+use pin_project_lite::pin_project;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+type Inner<F> = Scoped<Arc<Container>, Scoped<RefCell<ResolvingStack>, Scoped<Arc<DIScope>, F>>>;
+
+pin_project! {
+    /// Future returned by [`DIScope::enter`] and [`DiFutureExt::with_di_scope`].
+    #[must_use = "futures do nothing unless polled"]
+    pub struct WithDiScope<F: Future> {
+        #[pin]
+        inner: Inner<F>,
+    }
+}
+
+impl<F: Future> WithDiScope<F> {
+    pub(crate) fn new(inner: Inner<F>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<F: Future> Future for WithDiScope<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx)
+    }
+}
+
+/// Runs any future inside an existing [`DIScope`].
+///
+/// ```ignore
+/// use rust_di::DiFutureExt;
+///
+/// let scope = DIScope::current()?;
+/// stream.for_each(move |event| handle(event).with_di_scope(scope.clone()));
+/// ```
+pub trait DiFutureExt: Future + Sized {
+    /// Same as [`DIScope::enter`].
+    fn with_di_scope(self, scope: Arc<DIScope>) -> WithDiScope<Self> {
+        scope.enter(self)
+    }
+}
+
+impl<F: Future> DiFutureExt for F {}
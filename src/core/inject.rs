@@ -0,0 +1,52 @@
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Handler parameter resolved from the current [`DIScope`](crate::DIScope) by
+/// `#[rust_di::inject]` before the function body runs.
+///
+/// ```ignore
+/// #[rust_di::inject]
+/// async fn create_user(name: String, repo: Inject<UserRepo>) -> Result<User, DiError> {
+///     repo.insert(name).await
+/// }
+///
+/// create_user("alice".into()).await?;
+/// ```
+pub struct Inject<T: ?Sized>(pub Arc<T>);
+
+impl<T: ?Sized> Inject<T> {
+    pub fn new(instance: Arc<T>) -> Self {
+        Self(instance)
+    }
+
+    pub fn into_inner(self) -> Arc<T> {
+        self.0
+    }
+}
+
+impl<T: ?Sized> Deref for Inject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> Clone for Inject<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> From<Arc<T>> for Inject<T> {
+    fn from(instance: Arc<T>) -> Self {
+        Self(instance)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Inject<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Inject").field(&self.0).finish()
+    }
+}
@@ -0,0 +1,184 @@
+use crate::DIScope;
+use crate::core::context::{ContextKey, poll_local};
+use crate::core::contracts::{Lifetime, LocalInstance, ServiceKey};
+use crate::core::error_di::DiError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Локальний scope ніколи не покидає потік, тож вистачає thread-local
+poll_local! {
+    static CURRENT_LOCAL_SCOPE: Rc<LocalScope>;
+}
+
+/// A scope for `!Send` services (`Rc`, `RefCell`, thread-bound handles), used on a
+/// current-thread runtime or a `tokio::task::LocalSet`.
+///
+/// Local services are registered with the `register_local_*` functions and
+/// resolved with [`LocalScope::get_local`]; `Send` services still come from the
+/// [`DIScope`] it wraps.
+///
+/// ```ignore
+/// register_local_scope::<ScriptEngine, _, _>(|_| async { Ok(ScriptEngine::new()) }).await?;
+///
+/// LocalScope::run_with_scope(|| async {
+///     let local = LocalScope::current()?;
+///     let engine: Rc<ScriptEngine> = local.get_local().await?;
+///     let config: Arc<Config> = local.get().await?;
+///     Ok::<_, DiError>(())
+/// })
+/// .await?;
+/// ```
+pub struct LocalScope {
+    scope: Arc<DIScope>,
+    instances: RefCell<HashMap<ServiceKey, LocalInstance>>,
+    resolving: RefCell<Vec<ServiceKey>>,
+}
+
+impl fmt::Debug for LocalScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalScope")
+            .field("local_instances_count", &self.instances.borrow().len())
+            .field("scope", &self.scope)
+            .finish()
+    }
+}
+
+impl LocalScope {
+    /// Creates a local scope resolving `Send` services from `scope`.
+    pub fn new(scope: Arc<DIScope>) -> Rc<Self> {
+        Rc::new(LocalScope {
+            scope,
+            instances: RefCell::new(HashMap::new()),
+            resolving: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Like [`DIScope::run_with_scope`], with a [`LocalScope`] over the new scope
+    /// as [`LocalScope::current`].
+    pub async fn run_with_scope<F, RFut, ROutput>(func: F) -> ROutput
+    where
+        F: FnOnce() -> RFut,
+        RFut: Future<Output = ROutput>,
+    {
+        DIScope::run_with_scope(|| async {
+            let scope = DIScope::current().expect("`run_with_scope` sets the current scope");
+            LocalScope::new(scope).enter(func()).await
+        })
+        .await
+    }
+
+    /// Runs `fut` with this local scope as [`LocalScope::current`] and its
+    /// [`DIScope`] as [`DIScope::current`].
+    pub async fn enter<F: Future>(self: &Rc<Self>, fut: F) -> F::Output {
+        let scoped = CURRENT_LOCAL_SCOPE.scope_future(self.clone(), fut);
+        self.scope.enter(scoped).await
+    }
+
+    pub fn current() -> Result<Rc<LocalScope>, DiError> {
+        CURRENT_LOCAL_SCOPE
+            .with_current(|scope| scope.clone())
+            .map_err(|e| {
+                DiError::FactoryError(Box::new(std::io::Error::other(format!(
+                    "No local DI scope found on this thread: {e}",
+                ))))
+            })
+    }
+
+    /// The scope `Send` services are resolved from.
+    pub fn scope(&self) -> &Arc<DIScope> {
+        &self.scope
+    }
+
+    /// Resolves a `Send` service, see [`DIScope::get`].
+    pub async fn get<T>(&self) -> Result<Arc<T>, DiError>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.get_by_name::<T>("").await
+    }
+
+    pub async fn get_by_name<T>(&self, name: &str) -> Result<Arc<T>, DiError>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.scope
+            .enter(self.scope.clone().get_by_name::<T>(name))
+            .await
+    }
+
+    pub async fn get_local<T: 'static>(self: &Rc<Self>) -> Result<Rc<T>, DiError> {
+        self.get_local_by_name::<T>("").await
+    }
+
+    /// Resolves a service registered with `register_local_*`.
+    pub async fn get_local_by_name<T: 'static>(
+        self: &Rc<Self>,
+        name: &str,
+    ) -> Result<Rc<T>, DiError> {
+        let not_found = || DiError::ServiceNotFound(name.to_string());
+        let key = ServiceKey::lookup::<T>(name).ok_or_else(not_found)?;
+        let (lifetime, factory) = self
+            .scope
+            .container
+            .local_factories
+            .get(&key)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(not_found)?;
+
+        let cached = self.instances.borrow().get(&key).cloned();
+        let instance = match cached {
+            Some(instance) => instance,
+            None => {
+                let _resolving = LocalResolving::enter(self, key)?;
+                let instance = factory(self.clone()).await?;
+                if lifetime == Lifetime::Scoped {
+                    // Фабрика могла вже створити екземпляр рекурсивно: перемагає перший
+                    self.instances
+                        .borrow_mut()
+                        .entry(key)
+                        .or_insert(instance)
+                        .clone()
+                } else {
+                    instance
+                }
+            }
+        };
+
+        instance.downcast::<T>().map_err(|_| {
+            DiError::FactoryError(Box::new(std::io::Error::other(format!(
+                "Type mismatch: could not downcast to {}",
+                std::any::type_name::<T>()
+            ))))
+        })
+    }
+}
+
+/// Marks `key` as being resolved by the local scope until dropped.
+struct LocalResolving<'a> {
+    scope: &'a LocalScope,
+    key: ServiceKey,
+}
+
+impl<'a> LocalResolving<'a> {
+    fn enter(scope: &'a LocalScope, key: ServiceKey) -> Result<Self, DiError> {
+        let mut resolving = scope.resolving.borrow_mut();
+        if resolving.contains(&key) {
+            return Err(DiError::CircularDependency(key.name().to_string()));
+        }
+        resolving.push(key);
+        Ok(Self { scope, key })
+    }
+}
+
+impl Drop for LocalResolving<'_> {
+    fn drop(&mut self) {
+        let mut resolving = self.scope.resolving.borrow_mut();
+        if let Some(position) = resolving.iter().rposition(|key| *key == self.key) {
+            resolving.remove(position);
+        }
+    }
+}
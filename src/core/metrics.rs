@@ -0,0 +1,183 @@
+use crate::core::contracts::{Lifetime, ServiceInstance, ServiceKey};
+use crate::core::error_di::DiError;
+use arc_swap::ArcSwapOption;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Receiver of resolution metrics.
+///
+/// Every method has an empty default implementation, so a sink only needs to
+/// override the events it is interested in.
+#[allow(unused_variables)]
+pub trait MetricsSink: Send + Sync + 'static {
+    /// A service was resolved through `DIScope::get_by_name`.
+    fn resolution(&self, service: &str, name: &str, lifetime: Lifetime) {}
+
+    /// A resolution through `DIScope::get_by_name` failed: the service is not
+    /// registered, its factory failed, or a dependency could not be resolved.
+    fn resolution_failure(&self, service: &str, name: &str, error: &DiError) {}
+
+    /// A Singleton or Scoped service was served from its cache.
+    fn cache_hit(&self, service: &str, name: &str, lifetime: Lifetime) {}
+
+    /// A Singleton or Scoped service was not cached and its factory was invoked.
+    fn cache_miss(&self, service: &str, name: &str, lifetime: Lifetime) {}
+
+    /// A factory finished (successfully or not) after `elapsed`.
+    fn factory_latency(&self, service: &str, name: &str, lifetime: Lifetime, elapsed: Duration) {}
+
+    /// A factory returned an error.
+    fn factory_failure(&self, service: &str, name: &str, lifetime: Lifetime, error: &DiError) {}
+
+    /// The number of live scopes opened by `DIScope::run_with_scope` changed.
+    fn live_scopes(&self, count: usize) {}
+}
+
+static METRICS_SINK: ArcSwapOption<Box<dyn MetricsSink>> = ArcSwapOption::const_empty();
+static LIVE_SCOPES: AtomicUsize = AtomicUsize::new(0);
+
+/// Installs the global metrics sink, replacing the previous one.
+pub fn set_metrics_sink<S: MetricsSink>(sink: S) {
+    METRICS_SINK.store(Some(Arc::new(Box::new(sink))));
+}
+
+/// Removes the global metrics sink. Hooks become no-ops again.
+pub fn clear_metrics_sink() {
+    METRICS_SINK.store(None);
+}
+
+/// Number of scopes currently running inside `DIScope::run_with_scope`.
+pub fn live_scopes() -> usize {
+    LIVE_SCOPES.load(Ordering::Relaxed)
+}
+
+pub(crate) fn record_resolution(key: &ServiceKey, lifetime: Lifetime, cache_hit: Option<bool>) {
+    if let Some(sink) = METRICS_SINK.load().as_ref() {
+        sink.resolution(key.type_name(), key.name(), lifetime);
+        match cache_hit {
+            Some(true) => sink.cache_hit(key.type_name(), key.name(), lifetime),
+            Some(false) => sink.cache_miss(key.type_name(), key.name(), lifetime),
+            None => {}
+        }
+    }
+}
+
+pub(crate) fn record_resolution_failure(service: &str, name: &str, error: &DiError) {
+    if let Some(sink) = METRICS_SINK.load().as_ref() {
+        sink.resolution_failure(service, name, error);
+    }
+}
+
+pub(crate) async fn measure_factory<Fut>(
+    key: &ServiceKey,
+    lifetime: Lifetime,
+    factory: Fut,
+) -> Result<ServiceInstance, DiError>
+where
+    Fut: Future<Output = Result<ServiceInstance, DiError>>,
+{
+    let started = Instant::now();
+    let result = factory.await;
+
+    if let Some(sink) = METRICS_SINK.load().as_ref() {
+        sink.factory_latency(key.type_name(), key.name(), lifetime, started.elapsed());
+        if let Err(error) = &result {
+            sink.factory_failure(key.type_name(), key.name(), lifetime, error);
+        }
+    }
+
+    result
+}
+
+/// Tracks one live scope for as long as it is alive.
+pub(crate) struct LiveScopeGuard;
+
+impl LiveScopeGuard {
+    pub(crate) fn new() -> Self {
+        let count = LIVE_SCOPES.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(sink) = METRICS_SINK.load().as_ref() {
+            sink.live_scopes(count);
+        }
+        LiveScopeGuard
+    }
+}
+
+impl Drop for LiveScopeGuard {
+    fn drop(&mut self) {
+        let count = LIVE_SCOPES.fetch_sub(1, Ordering::Relaxed) - 1;
+        if let Some(sink) = METRICS_SINK.load().as_ref() {
+            sink.live_scopes(count);
+        }
+    }
+}
+
+/// `MetricsSink` that forwards every event to the [`metrics`] crate facade.
+///
+/// | Metric                             | Kind      | Labels                      |
+/// |:-----------------------------------|:----------|:----------------------------|
+/// | `rust_di_resolutions_total`        | counter   | `service`, `name`, `lifetime` |
+/// | `rust_di_resolution_failures_total` | counter  | `service`, `name`           |
+/// | `rust_di_cache_hits_total`         | counter   | `service`, `name`, `lifetime` |
+/// | `rust_di_cache_misses_total`       | counter   | `service`, `name`, `lifetime` |
+/// | `rust_di_factory_duration_seconds` | histogram | `service`, `name`, `lifetime` |
+/// | `rust_di_factory_failures_total`   | counter   | `service`, `name`, `lifetime` |
+/// | `rust_di_live_scopes`              | gauge     |                             |
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetricsCrateSink;
+
+impl MetricsSink for MetricsCrateSink {
+    fn resolution(&self, service: &str, name: &str, lifetime: Lifetime) {
+        metrics::counter!("rust_di_resolutions_total", labels(service, name, lifetime))
+            .increment(1);
+    }
+
+    fn resolution_failure(&self, service: &str, name: &str, _: &DiError) {
+        metrics::counter!(
+            "rust_di_resolution_failures_total",
+            "service" => service.to_string(),
+            "name" => name.to_string()
+        )
+        .increment(1);
+    }
+
+    fn cache_hit(&self, service: &str, name: &str, lifetime: Lifetime) {
+        metrics::counter!("rust_di_cache_hits_total", labels(service, name, lifetime)).increment(1);
+    }
+
+    fn cache_miss(&self, service: &str, name: &str, lifetime: Lifetime) {
+        metrics::counter!(
+            "rust_di_cache_misses_total",
+            labels(service, name, lifetime)
+        )
+        .increment(1);
+    }
+
+    fn factory_latency(&self, service: &str, name: &str, lifetime: Lifetime, elapsed: Duration) {
+        metrics::histogram!(
+            "rust_di_factory_duration_seconds",
+            labels(service, name, lifetime)
+        )
+        .record(elapsed.as_secs_f64());
+    }
+
+    fn factory_failure(&self, service: &str, name: &str, lifetime: Lifetime, _: &DiError) {
+        metrics::counter!(
+            "rust_di_factory_failures_total",
+            labels(service, name, lifetime)
+        )
+        .increment(1);
+    }
+
+    fn live_scopes(&self, count: usize) {
+        metrics::gauge!("rust_di_live_scopes").set(count as f64);
+    }
+}
+
+fn labels(service: &str, name: &str, lifetime: Lifetime) -> Vec<metrics::Label> {
+    vec![
+        metrics::Label::new("service", service.to_string()),
+        metrics::Label::new("name", name.to_string()),
+        metrics::Label::new("lifetime", lifetime.as_str()),
+    ]
+}
@@ -1,8 +1,22 @@
+pub mod container;
+pub(crate) mod context;
 pub mod contracts;
 pub mod di_inventory;
 pub mod error_di;
 pub mod factory;
+pub mod future_ext;
+pub mod inject;
+pub mod local;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod open_generic;
+pub mod profiling;
+#[cfg(feature = "rayon")]
+pub mod rayon;
 pub mod registry;
+pub mod retry;
+#[cfg(feature = "tokio")]
+pub mod task;
 
 #[cfg(test)]
 pub(crate) mod tests;
@@ -0,0 +1,48 @@
+use std::any::TypeId;
+
+/// Links every instantiation of a generic type to one family token.
+///
+/// `#[registry(..., open)]` implements it with a marker struct generated next to the impl:
+///
+/// ```ignore
+/// pub struct __RepoOpenFamily;
+///
+/// impl<T: Entity> OpenGeneric for Repo<T> {
+///     type Family = __RepoOpenFamily;
+/// }
+/// ```
+pub trait OpenGeneric {
+    /// Any `'static` type unique to the family, the same for all its members.
+    type Family: ?Sized + 'static;
+}
+
+/// A generic type with its type arguments erased, e.g. `Repo` for `Repo<Invoice>`.
+///
+/// Open-generic registrations are keyed by family, so one registration serves
+/// every instantiation of the type. Families are told apart by the `TypeId`
+/// of their token, not by the type's name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeFamily {
+    id: TypeId,
+    name: &'static str,
+}
+
+impl TypeFamily {
+    /// Family of `T`: `TypeFamily::of::<Repo<Invoice>>() == TypeFamily::token::<__RepoOpenFamily>()`.
+    pub fn of<T: OpenGeneric + ?Sized>() -> Self {
+        Self::token::<T::Family>()
+    }
+
+    /// Family identified by the token `F`, see [`OpenGeneric::Family`].
+    pub fn token<F: ?Sized + 'static>() -> Self {
+        TypeFamily {
+            id: TypeId::of::<F>(),
+            name: std::any::type_name::<F>(),
+        }
+    }
+
+    /// `std::any::type_name` of the family token.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
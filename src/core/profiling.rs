@@ -0,0 +1,152 @@
+use crate::core::context::{ContextKey, context_local};
+use crate::core::contracts::{ServiceInstance, ServiceKey};
+use crate::core::error_di::DiError;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+static PROFILING: AtomicBool = AtomicBool::new(false);
+static STARTED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+static TIMINGS: Mutex<Vec<SingletonTiming>> = Mutex::new(Vec::new());
+
+context_local! {
+    static NESTED_NANOS: Arc<AtomicU64>;
+}
+
+/// Construction time of a single Singleton.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SingletonTiming {
+    pub service: String,
+    pub name: String,
+    /// Time spent in the factory, including the Singletons it resolved.
+    pub total: Duration,
+    /// Time spent in the factory itself, excluding the Singletons it resolved.
+    pub self_time: Duration,
+}
+
+/// Singleton construction times collected between [`start_startup_profiling`]
+/// and [`finish_startup_profiling`], sorted by `total` descending.
+///
+/// `Display` renders the report as a table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StartupReport {
+    pub singletons: Vec<SingletonTiming>,
+    /// Wall-clock time between start and finish.
+    pub elapsed: Duration,
+}
+
+/// Starts recording Singleton construction times, discarding a previous unfinished run.
+pub fn start_startup_profiling() {
+    TIMINGS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    *STARTED_AT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+    PROFILING.store(true, Ordering::SeqCst);
+}
+
+/// Stops recording and returns everything recorded since [`start_startup_profiling`].
+pub fn finish_startup_profiling() -> StartupReport {
+    PROFILING.store(false, Ordering::SeqCst);
+
+    let elapsed = STARTED_AT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+        .map(|started| started.elapsed())
+        .unwrap_or_default();
+    let mut singletons = std::mem::take(&mut *TIMINGS.lock().unwrap_or_else(|e| e.into_inner()));
+    singletons.sort_by_key(|timing| std::cmp::Reverse(timing.total));
+
+    StartupReport {
+        singletons,
+        elapsed,
+    }
+}
+
+pub(crate) async fn measure_singleton<Fut>(
+    key: &ServiceKey,
+    factory: Fut,
+) -> Result<ServiceInstance, DiError>
+where
+    Fut: Future<Output = Result<ServiceInstance, DiError>>,
+{
+    if !PROFILING.load(Ordering::Relaxed) {
+        return factory.await;
+    }
+
+    let nested = Arc::new(AtomicU64::new(0));
+    let started = Instant::now();
+    let result = NESTED_NANOS.scope_future(nested.clone(), factory).await;
+    let total = started.elapsed();
+
+    // Час цього Singleton входить у "self" батьківського лише як залежність
+    let _ = NESTED_NANOS.with_current(|parent| {
+        parent.fetch_add(total.as_nanos() as u64, Ordering::Relaxed);
+    });
+
+    if result.is_ok() {
+        let self_time = total.saturating_sub(Duration::from_nanos(nested.load(Ordering::Relaxed)));
+        TIMINGS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(SingletonTiming {
+                service: key.type_name().to_string(),
+                name: key.name().to_string(),
+                total,
+                self_time,
+            });
+    }
+
+    result
+}
+
+impl fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<[String; 4]> = self
+            .singletons
+            .iter()
+            .map(|timing| {
+                [
+                    timing.service.clone(),
+                    timing.name.clone(),
+                    format!("{:.3?}", timing.total),
+                    format!("{:.3?}", timing.self_time),
+                ]
+            })
+            .collect();
+
+        let header = ["Singleton", "Name", "Total", "Self"];
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        write_row(f, &header.map(String::from), &widths)?;
+        writeln!(f, "{}", "-".repeat(widths.iter().sum::<usize>() + 6))?;
+        for row in &rows {
+            write_row(f, row, &widths)?;
+        }
+        write!(
+            f,
+            "{} singleton(s) built in {:.3?}",
+            rows.len(),
+            self.elapsed
+        )
+    }
+}
+
+fn write_row(f: &mut fmt::Formatter<'_>, row: &[String; 4], widths: &[usize; 4]) -> fmt::Result {
+    writeln!(
+        f,
+        "{:<w0$}  {:<w1$}  {:>w2$}  {:>w3$}",
+        row[0],
+        row[1],
+        row[2],
+        row[3],
+        w0 = widths[0],
+        w1 = widths[1],
+        w2 = widths[2],
+        w3 = widths[3],
+    )
+}
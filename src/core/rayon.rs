@@ -0,0 +1,125 @@
+//! Running rayon parallel iterators inside a [`DIScope`].
+
+use crate::DIScope;
+use rayon::iter::ParallelIterator;
+use rayon::iter::plumbing::{Consumer, Folder, UnindexedConsumer};
+use std::sync::Arc;
+
+/// Makes a [`DIScope`] current in the per-item closures (`map`, `filter`,
+/// `for_each`, ...) of a parallel iterator.
+///
+/// ```ignore
+/// use rayon::prelude::*;
+/// use rust_di::core::rayon::DiParallelIteratorExt;
+///
+/// let scope = DIScope::current()?;
+/// let totals: Vec<u64> = orders
+///     .par_iter()
+///     .with_di_scope(scope)
+///     .map(|order| {
+///         let pricing = DIScope::current().unwrap().get_sync::<Pricing>().unwrap();
+///         pricing.total(order)
+///     })
+///     .collect();
+/// ```
+///
+/// Applies to the adapters after it. The result is unindexed, so put indexed
+/// adapters (`enumerate`, `zip`, ...) before it; nested parallel iterators
+/// (e.g. in `flat_map`) need their own `with_di_scope`.
+pub trait DiParallelIteratorExt: ParallelIterator {
+    fn with_di_scope(self, scope: Arc<DIScope>) -> WithDiScopeIter<Self> {
+        WithDiScopeIter { base: self, scope }
+    }
+}
+
+impl<I: ParallelIterator> DiParallelIteratorExt for I {}
+
+/// Parallel iterator returned by [`DiParallelIteratorExt::with_di_scope`].
+#[derive(Debug, Clone)]
+#[must_use = "iterator adaptors are lazy and do nothing unless consumed"]
+pub struct WithDiScopeIter<I> {
+    base: I,
+    scope: Arc<DIScope>,
+}
+
+impl<I: ParallelIterator> ParallelIterator for WithDiScopeIter<I> {
+    type Item = I::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.base.drive_unindexed(Scoped::new(consumer, self.scope))
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.base.opt_len()
+    }
+}
+
+/// Consumer or folder running its work inside `scope`.
+struct Scoped<B> {
+    base: B,
+    scope: Arc<DIScope>,
+}
+
+impl<B> Scoped<B> {
+    fn new(base: B, scope: Arc<DIScope>) -> Self {
+        Self { base, scope }
+    }
+}
+
+impl<T, C: Consumer<T>> Consumer<T> for Scoped<C> {
+    type Folder = Scoped<C::Folder>;
+    type Reducer = C::Reducer;
+    type Result = C::Result;
+
+    fn split_at(self, index: usize) -> (Self, Self, Self::Reducer) {
+        let (left, right, reducer) = self.base.split_at(index);
+        (
+            Scoped::new(left, self.scope.clone()),
+            Scoped::new(right, self.scope),
+            reducer,
+        )
+    }
+
+    fn into_folder(self) -> Self::Folder {
+        Scoped::new(self.base.into_folder(), self.scope)
+    }
+
+    fn full(&self) -> bool {
+        self.base.full()
+    }
+}
+
+impl<T, C: UnindexedConsumer<T>> UnindexedConsumer<T> for Scoped<C> {
+    fn split_off_left(&self) -> Self {
+        Scoped::new(self.base.split_off_left(), self.scope.clone())
+    }
+
+    fn to_reducer(&self) -> Self::Reducer {
+        self.base.to_reducer()
+    }
+}
+
+impl<T, F: Folder<T>> Folder<T> for Scoped<F> {
+    type Result = F::Result;
+
+    fn consume(self, item: T) -> Self {
+        let base = self.scope.enter_sync(|| self.base.consume(item));
+        Scoped::new(base, self.scope)
+    }
+
+    fn consume_iter<I: IntoIterator<Item = T>>(self, iter: I) -> Self {
+        let base = self.scope.enter_sync(|| self.base.consume_iter(iter));
+        Scoped::new(base, self.scope)
+    }
+
+    fn complete(self) -> Self::Result {
+        self.scope.enter_sync(|| self.base.complete())
+    }
+
+    fn full(&self) -> bool {
+        self.base.full()
+    }
+}
@@ -1,121 +1,613 @@
 use crate::DIScope;
-use crate::core::contracts::{RegisteredInstances, ServiceInstance};
+use crate::core::container::{Container, OverrideGuard};
+use crate::core::contracts::{
+    DuplicatePolicy, Lifetime, LifetimeConflictPolicy, LocalFactory, LocalInstance, ServiceFactory,
+    ServiceInstance, ServiceKey,
+};
 use crate::core::error_di::DiError;
-use arc_swap::ArcSwap;
-use dashmap::DashMap;
+use crate::core::factory::DiFactory;
+use crate::core::local::LocalScope;
+use crate::core::open_generic::{OpenGeneric, TypeFamily};
+use crate::core::retry::RetryPolicy;
+use futures::future::{self, Either};
+use futures_timer::Delay;
 use rust_di::core::contracts::AnyService;
+use std::marker::PhantomData;
 use std::pin::Pin;
+use std::rc::Rc;
 use std::sync::Arc;
-use tokio::sync::OnceCell;
+use std::time::{Duration, Instant};
+
+/// A pending registration: `.await` it to register the factory.
+///
+/// Returned by the `register_*` functions; options are set before awaiting:
+///
+/// ```ignore
+/// register_singleton::<Mailer, _, _>(|_| async { Ok(Mailer::default()) })
+///     .policy(DuplicatePolicy::Replace)
+///     .await?;
+/// ```
+#[must_use = "registrations do nothing unless `.await`ed"]
+pub struct Registration<T, F, Fut> {
+    lifetime: Lifetime,
+    name: String,
+    factory: F,
+    eager: bool,
+    policy: Option<DuplicatePolicy>,
+    lifetime_conflict: Option<LifetimeConflictPolicy>,
+    timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
+    fallback: Option<ServiceFactory>,
+    _service: PhantomData<fn() -> (T, Fut)>,
+}
+
+impl<T, F, Fut> Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    fn new(lifetime: Lifetime, name: &str, factory: F) -> Self {
+        Self {
+            lifetime,
+            name: name.to_string(),
+            factory,
+            eager: false,
+            policy: None,
+            lifetime_conflict: None,
+            timeout: None,
+            retry: None,
+            fallback: None,
+            _service: PhantomData,
+        }
+    }
+
+    /// What happens when the key is already registered; defaults to
+    /// [`Container::duplicate_policy`].
+    pub fn policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// What happens when the key is already registered with another lifetime;
+    /// defaults to [`Container::lifetime_conflict_policy`].
+    pub fn on_lifetime_conflict(mut self, policy: LifetimeConflictPolicy) -> Self {
+        self.lifetime_conflict = Some(policy);
+        self
+    }
+
+    /// Fails a construction taking longer than `timeout` with `DiError::FactoryTimeout`;
+    /// defaults to [`Container::factory_timeout`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retries a failing construction according to `policy`. Only Singletons can be
+    /// retried: registering another lifetime fails with `DiError::InvalidRegistration`.
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Constructs the service with `fallback` when the factory fails, e.g. an
+    /// in-memory cache instead of Redis. [`Container::fallbacks`] reports why.
+    pub fn with_fallback<G, GFut>(mut self, fallback: G) -> Self
+    where
+        G: Fn(Arc<DIScope>) -> GFut + Send + Sync + 'static,
+        GFut: Future<Output = Result<T, DiError>> + Send + 'static,
+    {
+        self.fallback = Some(wrap_factory(fallback));
+        self
+    }
+
+    /// Falls back to the registered service `U`, resolved through the container
+    /// and converted with `From<Arc<U>>`.
+    pub fn with_fallback_to<U>(self) -> Self
+    where
+        U: Send + Sync + 'static,
+        T: From<Arc<U>>,
+    {
+        self.with_fallback(|scope| async move { Ok(T::from(scope.get::<U>().await?)) })
+    }
+
+    /// Falls back to `T` registered under `name`: a Singleton shares that instance.
+    pub fn with_fallback_name(mut self, name: &str) -> Self {
+        let key = ServiceKey::of::<T>(name);
+        self.fallback = Some(Arc::new(move |scope: Arc<DIScope>| {
+            Box::pin(scope.resolve(key))
+                as Pin<Box<dyn Future<Output = Result<ServiceInstance, DiError>> + Send>>
+        }));
+        self
+    }
+
+    /// Registers only when the key is free, otherwise does nothing.
+    pub fn if_missing(self) -> Self {
+        self.policy(DuplicatePolicy::KeepFirst)
+    }
+
+    async fn register(self) -> Result<bool, DiError> {
+        let mut outcomes = Container::current().apply_registrations(vec![self.into_pending()])?;
+        outcomes.pop().unwrap_or(Ok(false))
+    }
 
-pub(crate) static REGISTERED_SINGLETON_FACTORIES: RegisteredInstances = OnceCell::const_new();
-pub(crate) static REGISTERED_TRANSIENT_FACTORIES: RegisteredInstances = OnceCell::const_new();
-pub(crate) static REGISTERED_SCOPE_FACTORIES: RegisteredInstances = OnceCell::const_new();
+    /// Erases the service type; the container's default policies are applied later.
+    pub(crate) fn into_pending(self) -> PendingRegistration {
+        PendingRegistration {
+            lifetime: self.lifetime,
+            key: ServiceKey::of::<T>(&self.name),
+            factory: wrap_factory(self.factory),
+            eager: self.eager,
+            policy: self.policy,
+            lifetime_conflict: self.lifetime_conflict,
+            timeout: self.timeout,
+            retry: self.retry,
+            fallback: self.fallback,
+        }
+    }
+}
+
+/// A [`Registration`] without its service type, applied by the container.
+pub(crate) struct PendingRegistration {
+    pub(crate) lifetime: Lifetime,
+    pub(crate) key: ServiceKey,
+    pub(crate) factory: ServiceFactory,
+    pub(crate) eager: bool,
+    pub(crate) policy: Option<DuplicatePolicy>,
+    pub(crate) lifetime_conflict: Option<LifetimeConflictPolicy>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) retry: Option<RetryPolicy>,
+    pub(crate) fallback: Option<ServiceFactory>,
+}
+
+impl<T, F, Fut> IntoFuture for Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    type Output = Result<(), DiError>;
+    type IntoFuture = Pin<Box<dyn Future<Output = Result<(), DiError>> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move { self.register().await.map(|_| ()) })
+    }
+}
 
-pub(crate) static SINGLETON_CACHE: once_cell::sync::OnceCell<
-    DashMap<(String, String), ServiceInstance>,
-> = once_cell::sync::OnceCell::new();
+/// Erases the service type of `factory`.
+pub(crate) fn wrap_factory<T, F, Fut>(factory: F) -> ServiceFactory
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    let arc_factory = Arc::new(factory);
+    Arc::new(move |scope: Arc<DIScope>| {
+        let factory_cloned = arc_factory.clone();
+        Box::pin(async move {
+            let service = factory_cloned(scope).await?;
+            let arc_any: Arc<dyn AnyService + Send + Sync + 'static> = Arc::new(service);
+            Ok(arc_any as ServiceInstance)
+        }) as Pin<Box<dyn Future<Output = Result<ServiceInstance, DiError>> + Send>>
+    })
+}
+
+/// Fails constructions of `key` taking longer than `timeout` with `DiError::FactoryTimeout`.
+pub(crate) fn with_timeout(
+    factory: ServiceFactory,
+    key: ServiceKey,
+    timeout: Duration,
+) -> ServiceFactory {
+    Arc::new(move |scope: Arc<DIScope>| {
+        let construction = factory(scope);
+        Box::pin(async move {
+            let started = Instant::now();
+            match future::select(construction, Delay::new(timeout)).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(DiError::FactoryTimeout {
+                    key,
+                    elapsed: started.elapsed(),
+                }),
+            }
+        }) as Pin<Box<dyn Future<Output = Result<ServiceInstance, DiError>> + Send>>
+    })
+}
+
+/// Constructs `key` with `fallback` when `factory` fails and records the failure;
+/// a later successful `factory` clears it. The error of `factory` is returned
+/// when both fail.
+pub(crate) fn with_fallback(
+    factory: ServiceFactory,
+    fallback: ServiceFactory,
+    key: ServiceKey,
+) -> ServiceFactory {
+    Arc::new(move |scope: Arc<DIScope>| {
+        let factory = factory.clone();
+        let fallback = fallback.clone();
+        Box::pin(async move {
+            let container = scope.container.clone();
+            match factory(scope.clone()).await {
+                Ok(instance) => {
+                    container.fallbacks.remove(&key);
+                    Ok(instance)
+                }
+                Err(error) => match fallback(scope).await {
+                    Ok(instance) => {
+                        container.fallbacks.insert(key, error.to_string());
+                        Ok(instance)
+                    }
+                    Err(_) => Err(error),
+                },
+            }
+        }) as Pin<Box<dyn Future<Output = Result<ServiceInstance, DiError>> + Send>>
+    })
+}
+
+/// Erases the service type of a local `factory`.
+fn wrap_local_factory<T, F, Fut>(factory: F) -> LocalFactory
+where
+    T: 'static,
+    F: Fn(Rc<LocalScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + 'static,
+{
+    let arc_factory = Arc::new(factory);
+    Arc::new(move |scope: Rc<LocalScope>| {
+        let factory_cloned = arc_factory.clone();
+        Box::pin(async move {
+            let service = factory_cloned(scope).await?;
+            Ok(Rc::new(service) as LocalInstance)
+        }) as Pin<Box<dyn Future<Output = Result<LocalInstance, DiError>>>>
+    })
+}
 
 #[allow(dead_code)]
-pub(crate) async fn register_factory<T, F, Fut>(
-    name: &str,
-    factory: F,
-    registry: &'static RegisteredInstances,
-) -> Result<(), DiError>
+pub fn register_transient<T, F, Fut>(factory: F) -> Registration<T, F, Fut>
 where
     T: Send + Sync + 'static,
     F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<T, DiError>> + Send + 'static,
 {
-    let type_key = std::any::type_name::<T>().to_string();
-    let name_string = name.to_string();
-    let factories_arcswap = registry
-        .get_or_init(|| async { ArcSwap::from_pointee(DashMap::new()) })
-        .await;
+    Registration::new(Lifetime::Transient, "", factory)
+}
 
-    let key = (type_key, name_string.clone());
-    let factories = factories_arcswap.load();
+#[allow(dead_code)]
+pub fn register_transient_name<T, F, Fut>(name: &str, factory: F) -> Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    Registration::new(Lifetime::Transient, name, factory)
+}
 
-    use dashmap::mapref::entry::Entry;
-    match factories.entry(key.clone()) {
-        Entry::Occupied(_) => return Err(DiError::ServiceAlreadyRegistered(name_string)),
-        Entry::Vacant(entry) => {
-            let arc_factory = Arc::new(factory);
-            let wrapped_factory = Arc::new(move |scope: Arc<DIScope>| {
-                let factory_cloned = arc_factory.clone();
-                Box::pin(async move {
-                    let service = factory_cloned(scope).await?;
-                    let arc_any: Arc<dyn AnyService + Send + Sync + 'static> = Arc::new(service);
-                    Ok(arc_any as ServiceInstance)
-                })
-                    as Pin<Box<dyn Future<Output = Result<ServiceInstance, DiError>> + Send>>
-            });
-
-            entry.insert(wrapped_factory);
-        }
+#[allow(dead_code)]
+pub fn register_scope<T, F, Fut>(factory: F) -> Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    Registration::new(Lifetime::Scoped, "", factory)
+}
+
+#[allow(dead_code)]
+pub fn register_scope_name<T, F, Fut>(name: &str, factory: F) -> Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    Registration::new(Lifetime::Scoped, name, factory)
+}
+
+#[allow(dead_code)]
+pub fn register_singleton<T, F, Fut>(factory: F) -> Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    Registration::new(Lifetime::Singleton, "", factory)
+}
+
+#[allow(dead_code)]
+pub fn register_singleton_name<T, F, Fut>(name: &str, factory: F) -> Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    Registration::new(Lifetime::Singleton, name, factory)
+}
+
+#[allow(dead_code)]
+pub fn register_singleton_eager<T, F, Fut>(factory: F) -> Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    register_singleton_name_eager("", factory)
+}
+
+/// Registers a Singleton that `initialize()` constructs right after registration
+/// instead of on the first `get`.
+#[allow(dead_code)]
+pub fn register_singleton_name_eager<T, F, Fut>(name: &str, factory: F) -> Registration<T, F, Fut>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    Registration {
+        eager: true,
+        ..Registration::new(Lifetime::Singleton, name, factory)
     }
+}
 
-    Ok(())
+/// Registers the factory unless the key is already taken.
+///
+/// Returns `true` when the factory was registered, `false` when an existing
+/// registration was kept. Lets libraries provide defaults that applications override.
+#[allow(dead_code)]
+pub async fn try_register_transient<T, F, Fut>(factory: F) -> Result<bool, DiError>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    register_transient(factory).if_missing().register().await
+}
+
+#[allow(dead_code)]
+pub async fn try_register_transient_name<T, F, Fut>(name: &str, factory: F) -> Result<bool, DiError>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    register_transient_name(name, factory)
+        .if_missing()
+        .register()
+        .await
+}
+
+#[allow(dead_code)]
+pub async fn try_register_scope<T, F, Fut>(factory: F) -> Result<bool, DiError>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    register_scope(factory).if_missing().register().await
+}
+
+#[allow(dead_code)]
+pub async fn try_register_scope_name<T, F, Fut>(name: &str, factory: F) -> Result<bool, DiError>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    register_scope_name(name, factory)
+        .if_missing()
+        .register()
+        .await
+}
+
+#[allow(dead_code)]
+pub async fn try_register_singleton<T, F, Fut>(factory: F) -> Result<bool, DiError>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    register_singleton(factory).if_missing().register().await
+}
+
+#[allow(dead_code)]
+pub async fn try_register_singleton_name<T, F, Fut>(name: &str, factory: F) -> Result<bool, DiError>
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + Send + 'static,
+{
+    register_singleton_name(name, factory)
+        .if_missing()
+        .register()
+        .await
 }
 
+/// Replaces the registration of `T` (or adds it) until the returned guard is dropped.
+///
+/// Meant for swapping real services for fakes in tests and for hot patching;
+/// call [`OverrideGuard::persist`] to keep the new factory. A cached Singleton of
+/// the key is evicted on both replace and restore.
 #[allow(dead_code)]
-pub async fn register_transient<T, F, Fut>(factory: F) -> Result<(), DiError>
+pub async fn override_transient<T, F, Fut>(factory: F) -> OverrideGuard
 where
     T: Send + Sync + 'static,
     F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<T, DiError>> + Send + 'static,
 {
-    register_factory("", factory, &REGISTERED_TRANSIENT_FACTORIES).await
+    Container::current().replace::<T, _, _>(Lifetime::Transient, "", factory)
 }
 
 #[allow(dead_code)]
-pub async fn register_transient_name<T, F, Fut>(name: &str, factory: F) -> Result<(), DiError>
+pub async fn override_transient_name<T, F, Fut>(name: &str, factory: F) -> OverrideGuard
 where
     T: Send + Sync + 'static,
     F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<T, DiError>> + Send + 'static,
 {
-    register_factory(name, factory, &REGISTERED_TRANSIENT_FACTORIES).await
+    Container::current().replace::<T, _, _>(Lifetime::Transient, name, factory)
 }
 
 #[allow(dead_code)]
-pub async fn register_scope<T, F, Fut>(factory: F) -> Result<(), DiError>
+pub async fn override_scope<T, F, Fut>(factory: F) -> OverrideGuard
 where
     T: Send + Sync + 'static,
     F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<T, DiError>> + Send + 'static,
 {
-    register_factory("", factory, &REGISTERED_SCOPE_FACTORIES).await
+    Container::current().replace::<T, _, _>(Lifetime::Scoped, "", factory)
 }
 
 #[allow(dead_code)]
-pub async fn register_scope_name<T, F, Fut>(name: &str, factory: F) -> Result<(), DiError>
+pub async fn override_scope_name<T, F, Fut>(name: &str, factory: F) -> OverrideGuard
 where
     T: Send + Sync + 'static,
     F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<T, DiError>> + Send + 'static,
 {
-    register_factory(name, factory, &REGISTERED_SCOPE_FACTORIES).await
+    Container::current().replace::<T, _, _>(Lifetime::Scoped, name, factory)
 }
 
 #[allow(dead_code)]
-pub async fn register_singleton<T, F, Fut>(factory: F) -> Result<(), DiError>
+pub async fn override_singleton<T, F, Fut>(factory: F) -> OverrideGuard
 where
     T: Send + Sync + 'static,
     F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<T, DiError>> + Send + 'static,
 {
-    register_factory("", factory, &REGISTERED_SINGLETON_FACTORIES).await
+    Container::current().replace::<T, _, _>(Lifetime::Singleton, "", factory)
 }
 
 #[allow(dead_code)]
-pub async fn register_singleton_name<T, F, Fut>(name: &str, factory: F) -> Result<(), DiError>
+pub async fn override_singleton_name<T, F, Fut>(name: &str, factory: F) -> OverrideGuard
 where
     T: Send + Sync + 'static,
     F: Fn(Arc<DIScope>) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<T, DiError>> + Send + 'static,
 {
-    register_factory(name, factory, &REGISTERED_SINGLETON_FACTORIES).await
+    Container::current().replace::<T, _, _>(Lifetime::Singleton, name, factory)
+}
+
+/// Registers a `!Send` service created anew on every `LocalScope::get_local`.
+///
+/// The factory itself must be `Send + Sync`; the service and its future don't.
+pub async fn register_local_transient<T, F, Fut>(factory: F) -> Result<(), DiError>
+where
+    T: 'static,
+    F: Fn(Rc<LocalScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + 'static,
+{
+    register_local::<T>(Lifetime::Transient, "", wrap_local_factory(factory))
+}
+
+pub async fn register_local_transient_name<T, F, Fut>(name: &str, factory: F) -> Result<(), DiError>
+where
+    T: 'static,
+    F: Fn(Rc<LocalScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + 'static,
+{
+    register_local::<T>(Lifetime::Transient, name, wrap_local_factory(factory))
+}
+
+/// Registers a `!Send` service created once per `LocalScope`.
+pub async fn register_local_scope<T, F, Fut>(factory: F) -> Result<(), DiError>
+where
+    T: 'static,
+    F: Fn(Rc<LocalScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + 'static,
+{
+    register_local::<T>(Lifetime::Scoped, "", wrap_local_factory(factory))
+}
+
+pub async fn register_local_scope_name<T, F, Fut>(name: &str, factory: F) -> Result<(), DiError>
+where
+    T: 'static,
+    F: Fn(Rc<LocalScope>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, DiError>> + 'static,
+{
+    register_local::<T>(Lifetime::Scoped, name, wrap_local_factory(factory))
+}
+
+fn register_local<T: 'static>(
+    lifetime: Lifetime,
+    name: &str,
+    factory: LocalFactory,
+) -> Result<(), DiError> {
+    use dashmap::mapref::entry::Entry;
+    match Container::current()
+        .local_factories
+        .entry(ServiceKey::of::<T>(name))
+    {
+        Entry::Occupied(_) => Err(DiError::ServiceAlreadyRegistered(name.to_string())),
+        Entry::Vacant(entry) => {
+            entry.insert((lifetime, factory));
+            Ok(())
+        }
+    }
+}
+
+pub(crate) async fn register_open_generic<W: ?Sized + 'static>(
+    name: &str,
+    lifetime: Lifetime,
+) -> Result<(), DiError> {
+    Container::current().insert_open_family(&TypeFamily::token::<W>(), name, lifetime)
+}
+
+/// Registers the closed factory of `T` from its open-generic family, once per key.
+pub(crate) async fn materialize_open_generic<T: DiFactory + OpenGeneric>(
+    container: &Container,
+    name: &str,
+) -> Result<(), DiError> {
+    let family = TypeFamily::of::<T>();
+    let lifetime = container
+        .open_generic_families
+        .get(&(family, name.to_string()))
+        .map(|entry| *entry.value())
+        .ok_or_else(|| DiError::ServiceNotFound(name.to_string()))?;
+
+    // Інший виклик міг матеріалізувати цей тип раніше
+    let key = ServiceKey::of::<T>(name);
+    let mut factory = wrap_factory(|scope| T::create(scope));
+    if let Some(timeout) = container.factory_timeout() {
+        factory = with_timeout(factory, key, timeout);
+    }
+    container.insert_factory(
+        lifetime,
+        key,
+        factory,
+        DuplicatePolicy::KeepFirst,
+        LifetimeConflictPolicy::KeepExisting,
+    )?;
+    Ok(())
+}
+
+/// Registers every member of the family with the token `W` as a Transient.
+///
+/// `W` is the [`OpenGeneric::Family`] of the members, e.g. a `RepoFamily` marker.
+/// Members are resolved with `DIScope::get_open` and built through their `DiFactory`.
+#[allow(dead_code)]
+pub async fn register_open_transient<W: ?Sized + 'static>() -> Result<(), DiError> {
+    register_open_generic::<W>("", Lifetime::Transient).await
+}
+
+#[allow(dead_code)]
+pub async fn register_open_transient_name<W: ?Sized + 'static>(name: &str) -> Result<(), DiError> {
+    register_open_generic::<W>(name, Lifetime::Transient).await
+}
+
+#[allow(dead_code)]
+pub async fn register_open_scope<W: ?Sized + 'static>() -> Result<(), DiError> {
+    register_open_generic::<W>("", Lifetime::Scoped).await
+}
+
+#[allow(dead_code)]
+pub async fn register_open_scope_name<W: ?Sized + 'static>(name: &str) -> Result<(), DiError> {
+    register_open_generic::<W>(name, Lifetime::Scoped).await
+}
+
+#[allow(dead_code)]
+pub async fn register_open_singleton<W: ?Sized + 'static>() -> Result<(), DiError> {
+    register_open_generic::<W>("", Lifetime::Singleton).await
+}
+
+#[allow(dead_code)]
+pub async fn register_open_singleton_name<W: ?Sized + 'static>(name: &str) -> Result<(), DiError> {
+    register_open_generic::<W>(name, Lifetime::Singleton).await
 }
 
 #[cfg(test)]
@@ -149,13 +641,14 @@ mod tests {
     #[derive(Default)]
     struct DuplicateCheckService;
 
-    #[tokio::test]
+    // Власний контейнер: перша реєстрація не залежить від порядку тестів
+    #[rust_di::test]
     async fn test_unique_singleton_duplicate_should_fail() {
-        initialize().await;
-        let _ = register_singleton_name::<DuplicateCheckService, _, _>("duplicate", |_| async {
+        register_singleton_name::<DuplicateCheckService, _, _>("duplicate", |_| async {
             Ok(DuplicateCheckService)
         })
-        .await;
+        .await
+        .unwrap();
 
         let result =
             register_singleton_name::<DuplicateCheckService, _, _>("duplicate", |_| async {
@@ -0,0 +1,166 @@
+//! Retrying failed Singleton factories with exponential backoff.
+
+use crate::DIScope;
+use crate::core::contracts::{ServiceFactory, ServiceInstance, ServiceKey};
+use crate::core::error_di::DiError;
+use futures_timer::Delay;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How a failing Singleton factory is retried.
+///
+/// ```ignore
+/// register_singleton::<DbPool, _, _>(|_| DbPool::connect())
+///     .with_retry(
+///         RetryPolicy::new(5)
+///             .backoff(Duration::from_millis(100))
+///             .jitter(0.2)
+///             .cache_failure_for(Duration::from_secs(30)),
+///     )
+///     .await?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    cooldown: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// Up to `max_attempts` constructions per `get`, waiting 100ms after the
+    /// first failure and twice as long after every next one, at most 10s.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.0,
+            cooldown: None,
+        }
+    }
+
+    /// Wait after the first failed attempt.
+    pub fn backoff(mut self, initial: Duration) -> Self {
+        self.backoff = initial;
+        self
+    }
+
+    /// Upper bound of the wait between two attempts.
+    pub fn max_backoff(mut self, max: Duration) -> Self {
+        self.max_backoff = max;
+        self
+    }
+
+    /// Growth of the wait after every failed attempt; `2.0` by default.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Shortens every wait by a random part of up to `fraction` of it, so that
+    /// several instances don't retry in lockstep.
+    pub fn jitter(mut self, fraction: f64) -> Self {
+        self.jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// After all attempts failed, every `get` within `cooldown` returns the same
+    /// error instead of constructing again.
+    pub fn cache_failure_for(mut self, cooldown: Duration) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Wait after the failed `attempt` (starting at 1), without jitter.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        Duration::from_secs_f64(delay.min(self.max_backoff.as_secs_f64()))
+    }
+
+    fn jittered_delay(&self, attempt: u32) -> Duration {
+        let delay = self.delay(attempt);
+        if self.jitter == 0.0 {
+            return delay;
+        }
+        // Кожен RandomState має нові ключі, тож хеш — достатньо випадкове число
+        let random = (RandomState::new().hash_one(attempt) >> 11) as f64 / (1u64 << 53) as f64;
+        delay.mul_f64(1.0 - self.jitter * random)
+    }
+}
+
+/// The last failed construction of a Singleton, returned until `until`.
+struct Failure {
+    attempts: u32,
+    cause: Arc<DiError>,
+    until: Instant,
+}
+
+/// Retries `factory` according to `policy` and caches its failure for the cool-down.
+///
+/// The instance itself is cached by the Singleton cache, which also makes
+/// concurrent callers wait for the running construction.
+pub(crate) fn with_retry(
+    factory: ServiceFactory,
+    key: ServiceKey,
+    policy: RetryPolicy,
+) -> ServiceFactory {
+    let last_failure: Arc<Mutex<Option<Failure>>> = Arc::default();
+    Arc::new(move |scope: Arc<DIScope>| {
+        let factory = factory.clone();
+        let last_failure = last_failure.clone();
+        Box::pin(async move {
+            if let Some(failure) = last_failure
+                .lock()
+                .map_err(|_| DiError::LockPoisoned)?
+                .as_ref()
+                && Instant::now() < failure.until
+            {
+                return Err(DiError::FactoryFailed {
+                    key,
+                    attempts: failure.attempts,
+                    cause: failure.cause.clone(),
+                });
+            }
+
+            let mut attempt = 1;
+            let cause = loop {
+                match factory(scope.clone()).await {
+                    Ok(instance) => {
+                        *last_failure.lock().map_err(|_| DiError::LockPoisoned)? = None;
+                        return Ok(instance);
+                    }
+                    Err(error) if attempt >= policy.max_attempts => break Arc::new(error),
+                    Err(_) => {
+                        Delay::new(policy.jittered_delay(attempt)).await;
+                        attempt += 1;
+                    }
+                }
+            };
+
+            if let Some(cooldown) = policy.cooldown {
+                *last_failure.lock().map_err(|_| DiError::LockPoisoned)? = Some(Failure {
+                    attempts: attempt,
+                    cause: cause.clone(),
+                    until: Instant::now() + cooldown,
+                });
+            }
+            Err(DiError::FactoryFailed {
+                key,
+                attempts: attempt,
+                cause,
+            })
+        }) as Pin<Box<dyn Future<Output = Result<ServiceInstance, DiError>> + Send>>
+    })
+}
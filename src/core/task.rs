@@ -0,0 +1,93 @@
+use crate::DIScope;
+use crate::core::container::Container;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::task::{AbortHandle, JoinHandle, JoinSet};
+
+/// Which scope a spawned task resolves from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScopeMode {
+    /// The caller's scope: Scoped instances are shared both ways.
+    #[default]
+    Share,
+    /// A [`DIScope::fork`] of the caller's scope: the task sees the instances
+    /// built so far, the ones it builds stay in the task.
+    Fork,
+}
+
+/// Scope of a task spawned from here; a new one when the caller has none.
+fn task_scope(mode: ScopeMode) -> Arc<DIScope> {
+    match DIScope::current() {
+        Ok(scope) => match mode {
+            ScopeMode::Share => scope,
+            ScopeMode::Fork => scope.fork(),
+        },
+        Err(_) => DIScope::over(Container::current()),
+    }
+}
+
+/// `tokio::spawn` keeping the caller's DI scope (see [`ScopeMode::Share`]).
+pub fn spawn<F>(fut: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    spawn_with(ScopeMode::Share, fut)
+}
+
+/// `tokio::spawn` resolving from the caller's scope as `mode` says.
+pub fn spawn_with<F>(mode: ScopeMode, fut: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(task_scope(mode).enter(fut))
+}
+
+/// `tokio::task::spawn_blocking` keeping the caller's DI scope.
+///
+/// `DIScope::current()` works inside `f`; resolving still needs a runtime,
+/// e.g. `Handle::current().block_on(scope.get::<T>())`.
+pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    spawn_blocking_with(ScopeMode::Share, f)
+}
+
+pub fn spawn_blocking_with<F, R>(mode: ScopeMode, f: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let scope = task_scope(mode);
+    tokio::task::spawn_blocking(move || scope.enter_sync(f))
+}
+
+/// Spawning into a `JoinSet` while keeping the caller's DI scope.
+pub trait ScopedJoinSet<T> {
+    fn spawn_scoped<F>(&mut self, fut: F) -> AbortHandle
+    where
+        F: Future<Output = T> + Send + 'static;
+
+    fn spawn_scoped_with<F>(&mut self, mode: ScopeMode, fut: F) -> AbortHandle
+    where
+        F: Future<Output = T> + Send + 'static;
+}
+
+impl<T: Send + 'static> ScopedJoinSet<T> for JoinSet<T> {
+    fn spawn_scoped<F>(&mut self, fut: F) -> AbortHandle
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.spawn_scoped_with(ScopeMode::Share, fut)
+    }
+
+    fn spawn_scoped_with<F>(&mut self, mode: ScopeMode, fut: F) -> AbortHandle
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.spawn(task_scope(mode).enter(fut))
+    }
+}
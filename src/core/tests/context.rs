@@ -0,0 +1,96 @@
+use crate::core::context::{ContextKey, NotInContext, poll_local};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::Poll;
+
+poll_local! {
+    static REQUEST: usize;
+}
+
+fn current() -> Result<usize, NotInContext> {
+    REQUEST.with_current(|request| *request)
+}
+
+#[tokio::test]
+async fn test_poll_local_is_set_only_inside_its_future() {
+    assert_eq!(current(), Err(NotInContext));
+
+    let (inner, outer) = REQUEST
+        .scope_future(1, async {
+            let inner = REQUEST
+                .scope_future(2, async {
+                    tokio::task::yield_now().await;
+                    current().unwrap()
+                })
+                .await;
+            (inner, current().unwrap())
+        })
+        .await;
+
+    assert_eq!((inner, outer), (2, 1));
+    assert_eq!(REQUEST.scope_sync(3, current), Ok(3));
+    assert_eq!(current(), Err(NotInContext));
+}
+
+#[tokio::test]
+async fn test_poll_local_keeps_interleaved_futures_apart() {
+    let observe = |request| {
+        REQUEST.scope_future(request, async move {
+            let mut seen = Vec::new();
+            for _ in 0..3 {
+                seen.push(current().unwrap());
+                tokio::task::yield_now().await;
+            }
+            assert!(seen.iter().all(|seen| *seen == request));
+        })
+    };
+    futures::future::join(observe(1), observe(2)).await;
+}
+
+#[tokio::test]
+async fn test_poll_local_drops_an_unfinished_future_inside_its_scope() {
+    static SEEN_ON_DROP: AtomicUsize = AtomicUsize::new(0);
+
+    struct Probe;
+    impl Drop for Probe {
+        fn drop(&mut self) {
+            SEEN_ON_DROP.store(current().unwrap_or(0), Ordering::SeqCst);
+        }
+    }
+
+    let pending = REQUEST.scope_future(7, async {
+        let _probe = Probe;
+        std::future::pending::<()>().await;
+    });
+    let mut pending = Box::pin(pending);
+    let polled = std::future::poll_fn(|cx| Poll::Ready(pending.as_mut().poll(cx))).await;
+    assert!(polled.is_pending());
+    drop(pending);
+
+    assert_eq!(SEEN_ON_DROP.load(Ordering::SeqCst), 7);
+}
+
+#[test]
+fn test_scope_works_without_a_tokio_runtime() {
+    use crate::DIScope;
+    use crate::core::container::Container;
+    use crate::core::registry::register_scope;
+    use std::sync::Arc;
+
+    struct Session;
+
+    let container = Container::new();
+    let (first, second) = futures::executor::block_on(container.run(async {
+        register_scope::<Session, _, _>(|_| async { Ok(Session) })
+            .await
+            .unwrap();
+        DIScope::run_with_scope(|| async {
+            let scope = DIScope::current().unwrap();
+            (
+                scope.clone().get::<Session>().await.unwrap(),
+                scope.get::<Session>().await.unwrap(),
+            )
+        })
+        .await
+    }));
+    assert!(Arc::ptr_eq(&first, &second));
+}
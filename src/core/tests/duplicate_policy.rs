@@ -0,0 +1,157 @@
+use crate::DIScope;
+use crate::core::container::Container;
+use crate::core::contracts::DuplicatePolicy;
+use crate::core::error_di::DiError;
+use crate::core::registry::{
+    register_singleton, register_transient_name, try_register_scope, try_register_singleton,
+};
+
+struct PolicyTheme(&'static str);
+
+impl Default for PolicyTheme {
+    fn default() -> Self {
+        Self("library")
+    }
+}
+
+#[rust_di::registry(Singleton(if_missing))]
+impl PolicyTheme {}
+
+struct PolicyLocale(&'static str);
+
+impl Default for PolicyLocale {
+    fn default() -> Self {
+        Self("library")
+    }
+}
+
+#[rust_di::registry(Singleton)]
+impl PolicyLocale {}
+
+struct PolicyPlugin(&'static str);
+
+async fn application_defaults() -> Result<(), DiError> {
+    Container::current().set_duplicate_policy(DuplicatePolicy::Replace);
+    register_singleton::<PolicyTheme, _, _>(|_| async { Ok(PolicyTheme("application")) }).await?;
+    register_singleton::<PolicyLocale, _, _>(|_| async { Ok(PolicyLocale("application")) }).await
+}
+
+#[rust_di::test(setup = application_defaults)]
+async fn test_if_missing_keeps_existing_registration_under_replace_policy() {
+    let scope = DIScope::current().unwrap();
+    assert_eq!(
+        scope.clone().get::<PolicyTheme>().await.unwrap().0,
+        "application"
+    );
+    assert_eq!(scope.get::<PolicyLocale>().await.unwrap().0, "library");
+}
+
+#[rust_di::test]
+async fn test_try_register_reports_whether_factory_was_added() {
+    assert!(
+        try_register_scope::<PolicyPlugin, _, _>(|_| async { Ok(PolicyPlugin("first")) })
+            .await
+            .unwrap()
+    );
+    assert!(
+        !try_register_scope::<PolicyPlugin, _, _>(|_| async { Ok(PolicyPlugin("second")) })
+            .await
+            .unwrap()
+    );
+    // Ключ уже зареєстрований через inventory
+    assert!(
+        !try_register_singleton::<PolicyLocale, _, _>(|_| async { Ok(PolicyLocale("late")) })
+            .await
+            .unwrap()
+    );
+
+    let scope = DIScope::current().unwrap();
+    assert_eq!(scope.get::<PolicyPlugin>().await.unwrap().0, "first");
+}
+
+#[rust_di::test]
+async fn test_replace_policy_evicts_cached_singleton() {
+    let scope = DIScope::current().unwrap();
+    assert_eq!(
+        scope.clone().get::<PolicyLocale>().await.unwrap().0,
+        "library"
+    );
+
+    let duplicate =
+        register_singleton::<PolicyLocale, _, _>(|_| async { Ok(PolicyLocale("patched")) }).await;
+    assert!(matches!(
+        duplicate,
+        Err(DiError::ServiceAlreadyRegistered(_))
+    ));
+
+    register_singleton::<PolicyLocale, _, _>(|_| async { Ok(PolicyLocale("patched")) })
+        .policy(DuplicatePolicy::Replace)
+        .await
+        .unwrap();
+    assert_eq!(scope.get::<PolicyLocale>().await.unwrap().0, "patched");
+}
+
+#[rust_di::test]
+async fn test_append_policy_collects_every_registration() {
+    for plugin in ["auth", "audit", "cache"] {
+        register_transient_name::<PolicyPlugin, _, _>("middleware", move |_| async move {
+            Ok(PolicyPlugin(plugin))
+        })
+        .policy(DuplicatePolicy::Append)
+        .await
+        .unwrap();
+    }
+
+    let scope = DIScope::current().unwrap();
+    let first = scope
+        .clone()
+        .get_by_name::<PolicyPlugin>("middleware")
+        .await
+        .unwrap();
+    assert_eq!(first.0, "auth");
+
+    let all = scope
+        .clone()
+        .get_all_by_name::<PolicyPlugin>("middleware")
+        .await
+        .unwrap();
+    let names: Vec<_> = all.iter().map(|plugin| plugin.0).collect();
+    assert_eq!(names, ["auth", "audit", "cache"]);
+
+    assert!(scope.get_all::<PolicyPlugin>().await.unwrap().is_empty());
+}
+
+#[rust_di::test]
+async fn test_container_wide_keep_first_policy() {
+    Container::current().set_duplicate_policy(DuplicatePolicy::KeepFirst);
+    register_singleton::<PolicyLocale, _, _>(|_| async { Ok(PolicyLocale("ignored")) })
+        .await
+        .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    assert_eq!(scope.get::<PolicyLocale>().await.unwrap().0, "library");
+}
+
+struct PolicyMailer(&'static str);
+
+#[rust_di::test]
+async fn test_replace_is_not_undone_by_a_construction_in_flight() {
+    register_singleton::<PolicyMailer, _, _>(|_| async {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        Ok(PolicyMailer("old"))
+    })
+    .await
+    .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    let (in_flight, replaced) = futures::join!(scope.clone().get::<PolicyMailer>(), async {
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        register_singleton::<PolicyMailer, _, _>(|_| async { Ok(PolicyMailer("new")) })
+            .policy(DuplicatePolicy::Replace)
+            .await
+    });
+
+    replaced.unwrap();
+    assert_eq!(in_flight.unwrap().0, "old");
+    assert_eq!(scope.get::<PolicyMailer>().await.unwrap().0, "new");
+}
@@ -0,0 +1,62 @@
+use crate::core::error_di::DiError;
+use crate::{DIScope, initialize};
+use std::sync::Arc;
+
+#[derive(Default)]
+struct FactoryFnConfig {
+    pub url: &'static str,
+}
+
+#[rust_di::registry(Singleton)]
+impl FactoryFnConfig {}
+
+struct FactoryFnPool {
+    pub url: &'static str,
+    pub replica: Option<Arc<FactoryFnConfig>>,
+}
+
+#[rust_di::factory(Singleton, name = "primary")]
+async fn make_pool(
+    cfg: Arc<FactoryFnConfig>,
+    #[inject(name = "replica")] replica: Option<Arc<FactoryFnConfig>>,
+) -> Result<FactoryFnPool, DiError> {
+    Ok(FactoryFnPool {
+        url: if cfg.url.is_empty() {
+            "postgres://"
+        } else {
+            cfg.url
+        },
+        replica,
+    })
+}
+
+struct FactoryFnClient(pub Arc<FactoryFnPool>);
+
+#[rust_di::factory(Transient)]
+async fn make_client(#[inject(name = "primary")] pool: Arc<FactoryFnPool>) -> FactoryFnClient {
+    FactoryFnClient(pool)
+}
+
+#[tokio::test]
+async fn test_factory_fn_registers_return_type() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        let pool = scope
+            .clone()
+            .get_by_name::<FactoryFnPool>("primary")
+            .await
+            .unwrap();
+        assert_eq!(pool.url, "postgres://");
+        assert!(pool.replica.is_none());
+
+        let client = scope.clone().get::<FactoryFnClient>().await.unwrap();
+        assert!(Arc::ptr_eq(&client.0, &pool));
+
+        let direct = make_pool(Arc::new(FactoryFnConfig { url: "direct" }), None)
+            .await
+            .unwrap();
+        assert_eq!(direct.url, "direct");
+    })
+    .await;
+}
@@ -0,0 +1,83 @@
+use crate::DIScope;
+use crate::core::container::Container;
+use crate::core::contracts::{DuplicatePolicy, ServiceKey};
+use crate::core::error_di::DiError;
+use crate::core::factory::DiFactory;
+use crate::core::registry::{register_transient, register_transient_name};
+use async_trait::async_trait;
+use futures_timer::Delay;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct Broker;
+
+#[async_trait]
+impl DiFactory for Broker {
+    async fn create(_scope: Arc<DIScope>) -> Result<Self, DiError> {
+        futures::future::pending().await
+    }
+}
+
+#[rust_di::registry(Singleton(factory, timeout = "50ms"))]
+impl Broker {}
+
+struct Slow;
+
+async fn slow(delay: Duration) -> Result<Slow, DiError> {
+    Delay::new(delay).await;
+    Ok(Slow)
+}
+
+#[rust_di::test]
+async fn test_registry_timeout_fails_hanging_factory() {
+    let scope = DIScope::current().unwrap();
+
+    match scope.get::<Broker>().await {
+        Err(DiError::FactoryTimeout { key, elapsed }) => {
+            assert_eq!(key, ServiceKey::of::<Broker>(""));
+            assert!(elapsed >= Duration::from_millis(50));
+        }
+        other => panic!("expected a timeout, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[rust_di::test]
+async fn test_with_timeout_only_fails_slow_constructions() {
+    register_transient::<Slow, _, _>(|_| slow(Duration::from_millis(30)))
+        .with_timeout(Duration::from_secs(5))
+        .await
+        .unwrap();
+    let scope = DIScope::current().unwrap();
+    assert!(scope.clone().get::<Slow>().await.is_ok());
+
+    register_transient::<Slow, _, _>(|_| slow(Duration::from_secs(5)))
+        .with_timeout(Duration::from_millis(20))
+        .policy(DuplicatePolicy::Replace)
+        .await
+        .unwrap();
+    let error = scope.get::<Slow>().await.err().unwrap();
+    assert!(matches!(error, DiError::FactoryTimeout { .. }));
+    assert!(error.to_string().contains("timed out"));
+}
+
+#[rust_di::test]
+async fn test_container_timeout_applies_unless_registration_sets_one() {
+    let container = Container::current();
+    container.set_factory_timeout(Some(Duration::from_millis(20)));
+    assert_eq!(container.factory_timeout(), Some(Duration::from_millis(20)));
+
+    register_transient::<Slow, _, _>(|_| slow(Duration::from_secs(5)))
+        .await
+        .unwrap();
+    register_transient_name::<Slow, _, _>("patient", |_| slow(Duration::from_millis(50)))
+        .with_timeout(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    assert!(matches!(
+        scope.clone().get::<Slow>().await,
+        Err(DiError::FactoryTimeout { .. })
+    ));
+    assert!(scope.get_by_name::<Slow>("patient").await.is_ok());
+}
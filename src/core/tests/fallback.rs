@@ -0,0 +1,197 @@
+use crate::DIScope;
+use crate::core::container::{Container, FallbackInfo};
+use crate::core::contracts::{DuplicatePolicy, LifetimeConflictPolicy};
+use crate::core::error_di::DiError;
+use crate::core::factory::DiFactory;
+use crate::core::registry::{register_singleton_name, register_transient};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+fn unreachable_redis() -> DiError {
+    DiError::ServiceNotFound("redis".to_string())
+}
+
+/// Stands in for Redis while it is unreachable.
+struct InMemoryCache;
+
+#[async_trait]
+impl DiFactory for InMemoryCache {
+    async fn create(_scope: Arc<DIScope>) -> Result<Self, DiError> {
+        Ok(InMemoryCache)
+    }
+}
+
+#[rust_di::registry(Singleton(factory))]
+impl InMemoryCache {}
+
+enum ResponseCache {
+    Redis,
+    Memory(Arc<InMemoryCache>),
+}
+
+impl ResponseCache {
+    fn backend(&self) -> &'static str {
+        match self {
+            ResponseCache::Redis => "redis",
+            ResponseCache::Memory(_) => "memory",
+        }
+    }
+}
+
+impl From<Arc<InMemoryCache>> for ResponseCache {
+    fn from(memory: Arc<InMemoryCache>) -> Self {
+        ResponseCache::Memory(memory)
+    }
+}
+
+#[async_trait]
+impl DiFactory for ResponseCache {
+    async fn create(_scope: Arc<DIScope>) -> Result<Self, DiError> {
+        Err(unreachable_redis())
+    }
+}
+
+#[rust_di::registry(Singleton(factory, fallback = InMemoryCache))]
+impl ResponseCache {}
+
+struct SearchIndex(&'static str);
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        SearchIndex("replica")
+    }
+}
+
+#[async_trait]
+impl DiFactory for SearchIndex {
+    async fn create(_scope: Arc<DIScope>) -> Result<Self, DiError> {
+        Err(DiError::ServiceNotFound("primary".to_string()))
+    }
+}
+
+#[rust_di::registry(Singleton(factory, fallback = "replica"), Singleton(name = "replica"))]
+impl SearchIndex {}
+
+trait SessionStore: Send + Sync {
+    fn backend(&self) -> &'static str;
+}
+
+struct MemoryStore;
+
+impl SessionStore for MemoryStore {
+    fn backend(&self) -> &'static str {
+        "memory"
+    }
+}
+
+#[rust_di::test]
+async fn test_registry_fallback_is_used_and_reported() {
+    let scope = DIScope::current().unwrap();
+    let cache = scope.clone().get::<ResponseCache>().await.unwrap();
+    let ResponseCache::Memory(memory) = &*cache else {
+        panic!("expected the in-memory fallback");
+    };
+    let registered = scope.get::<InMemoryCache>().await.unwrap();
+    assert!(Arc::ptr_eq(memory, &registered));
+
+    assert_eq!(
+        Container::current().fallbacks(),
+        vec![FallbackInfo {
+            service: std::any::type_name::<ResponseCache>().to_string(),
+            name: String::new(),
+            reason: unreachable_redis().to_string(),
+        }]
+    );
+}
+
+#[rust_di::test]
+async fn test_registry_fallback_to_named_registration_shares_its_instance() {
+    let scope = DIScope::current().unwrap();
+    let index = scope.clone().get::<SearchIndex>().await.unwrap();
+    let replica = scope.get_by_name::<SearchIndex>("replica").await.unwrap();
+
+    assert_eq!(index.0, "replica");
+    assert!(Arc::ptr_eq(&index, &replica));
+    assert_eq!(Container::current().fallbacks().len(), 1);
+}
+
+#[rust_di::test]
+async fn test_named_trait_registration_falls_back() {
+    register_singleton_name::<Arc<dyn SessionStore>, _, _>("memory", |_| async {
+        Ok(Arc::new(MemoryStore) as Arc<dyn SessionStore>)
+    })
+    .await
+    .unwrap();
+    register_singleton_name::<Arc<dyn SessionStore>, _, _>("sessions", |_| async {
+        Err(unreachable_redis())
+    })
+    .with_fallback_name("memory")
+    .await
+    .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    let store = scope
+        .get_by_name::<Arc<dyn SessionStore>>("sessions")
+        .await
+        .unwrap();
+    assert_eq!(store.backend(), "memory");
+    assert_eq!(Container::current().fallbacks()[0].name, "sessions");
+}
+
+#[rust_di::test]
+async fn test_fallback_is_cleared_once_factory_recovers() {
+    let ready = Arc::new(AtomicBool::new(false));
+    let factory_ready = ready.clone();
+    register_transient::<ResponseCache, _, _>(move |_| {
+        let ready = factory_ready.load(Ordering::SeqCst);
+        async move {
+            if ready {
+                Ok(ResponseCache::Redis)
+            } else {
+                Err(unreachable_redis())
+            }
+        }
+    })
+    .with_fallback_to::<InMemoryCache>()
+    .policy(DuplicatePolicy::Replace)
+    .on_lifetime_conflict(LifetimeConflictPolicy::Replace)
+    .await
+    .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    assert_eq!(
+        scope
+            .clone()
+            .get::<ResponseCache>()
+            .await
+            .unwrap()
+            .backend(),
+        "memory"
+    );
+    assert_eq!(Container::current().fallbacks().len(), 1);
+
+    ready.store(true, Ordering::SeqCst);
+    assert_eq!(
+        scope.get::<ResponseCache>().await.unwrap().backend(),
+        "redis"
+    );
+    assert!(Container::current().fallbacks().is_empty());
+}
+
+#[rust_di::test]
+async fn test_factory_error_is_returned_when_fallback_fails_too() {
+    register_singleton_name::<Arc<dyn SessionStore>, _, _>("sessions", |_| async {
+        Err(unreachable_redis())
+    })
+    .with_fallback_name("memory")
+    .await
+    .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    assert!(matches!(
+        scope.get_by_name::<Arc<dyn SessionStore>>("sessions").await,
+        Err(DiError::ServiceNotFound(name)) if name == "redis"
+    ));
+    assert!(Container::current().fallbacks().is_empty());
+}
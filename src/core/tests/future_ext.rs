@@ -0,0 +1,52 @@
+use crate::core::container::Container;
+use crate::core::registry::register_scope;
+use crate::{DIScope, DiFutureExt};
+use std::sync::Arc;
+
+struct Session;
+
+async fn resolve_session() -> Arc<Session> {
+    DIScope::current().unwrap().get::<Session>().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_enter_reuses_an_existing_scope() {
+    let container = Container::new();
+    let (scope, first) = container
+        .run(async {
+            register_scope::<Session, _, _>(|_| async { Ok(Session) })
+                .await
+                .unwrap();
+            DIScope::run_with_scope(|| async {
+                (DIScope::current().unwrap(), resolve_session().await)
+            })
+            .await
+        })
+        .await;
+
+    // Поза run_with_scope і поза контейнером
+    assert!(DIScope::current().is_err());
+    let again = scope.enter(resolve_session()).await;
+    let later = resolve_session().with_di_scope(scope.clone()).await;
+
+    assert!(Arc::ptr_eq(&first, &again));
+    assert!(Arc::ptr_eq(&first, &later));
+}
+
+#[rust_di::test]
+async fn test_with_di_scope_runs_concurrent_futures_in_one_scope() {
+    register_scope::<Session, _, _>(|_| async { Ok(Session) })
+        .await
+        .unwrap();
+    let scope = DIScope::current().unwrap();
+    let other = scope.fork();
+
+    let sessions =
+        futures::future::join_all((0..4).map(|_| resolve_session().with_di_scope(scope.clone())))
+            .await;
+    let forked = resolve_session().with_di_scope(other).await;
+
+    let own = scope.get::<Session>().await.unwrap();
+    assert!(sessions.iter().all(|session| Arc::ptr_eq(session, &own)));
+    assert!(!Arc::ptr_eq(&forked, &own));
+}
@@ -0,0 +1,116 @@
+use crate::core::container::Container;
+use crate::core::error_di::DiError;
+use crate::core::registry::register_singleton_name_eager;
+use crate::warm_up;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+struct SlowPool;
+
+#[derive(Default)]
+struct InFlight {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+    built: AtomicUsize,
+}
+
+const POOLS: [&str; 6] = ["a", "b", "c", "d", "e", "f"];
+
+#[rust_di::test]
+async fn test_warm_up_respects_init_concurrency() {
+    let container = Container::current();
+    container.set_init_concurrency(2);
+    let in_flight = Arc::new(InFlight::default());
+
+    for name in POOLS {
+        let in_flight = in_flight.clone();
+        register_singleton_name_eager::<SlowPool, _, _>(name, move |_| {
+            let in_flight = in_flight.clone();
+            async move {
+                let now = in_flight.current.fetch_add(1, Ordering::SeqCst) + 1;
+                in_flight.peak.fetch_max(now, Ordering::SeqCst);
+                for _ in 0..3 {
+                    tokio::task::yield_now().await;
+                }
+                in_flight.current.fetch_sub(1, Ordering::SeqCst);
+                in_flight.built.fetch_add(1, Ordering::SeqCst);
+                Ok(SlowPool)
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    warm_up().await.unwrap();
+
+    assert_eq!(in_flight.built.load(Ordering::SeqCst), POOLS.len());
+    assert_eq!(in_flight.peak.load(Ordering::SeqCst), 2);
+}
+
+#[rust_di::test]
+async fn test_init_concurrency_is_at_least_one() {
+    let container = Container::current();
+    assert_eq!(container.init_concurrency(), 16);
+
+    container.set_init_concurrency(0);
+    assert_eq!(container.init_concurrency(), 1);
+}
+
+struct EagerPool;
+struct EagerRepo;
+
+#[rust_di::test]
+async fn test_dependent_eager_singletons_are_built_once() {
+    let builds = Arc::new(AtomicUsize::new(0));
+
+    let counter = builds.clone();
+    register_singleton_name_eager::<EagerPool, _, _>("", move |_| {
+        let counter = counter.clone();
+        async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            for _ in 0..3 {
+                tokio::task::yield_now().await;
+            }
+            Ok(EagerPool)
+        }
+    })
+    .await
+    .unwrap();
+    register_singleton_name_eager::<EagerRepo, _, _>("", |scope| async move {
+        scope.get::<EagerPool>().await?;
+        Ok(EagerRepo)
+    })
+    .await
+    .unwrap();
+
+    warm_up().await.unwrap();
+
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+}
+
+struct CyclicLeft;
+struct CyclicRight;
+
+#[rust_di::test]
+async fn test_cyclic_eager_singletons_fail_instead_of_deadlocking() {
+    register_singleton_name_eager::<CyclicLeft, _, _>("", |scope| async move {
+        tokio::task::yield_now().await;
+        scope.get::<CyclicRight>().await?;
+        Ok(CyclicLeft)
+    })
+    .await
+    .unwrap();
+    register_singleton_name_eager::<CyclicRight, _, _>("", |scope| async move {
+        tokio::task::yield_now().await;
+        scope.get::<CyclicLeft>().await?;
+        Ok(CyclicRight)
+    })
+    .await
+    .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), warm_up())
+        .await
+        .expect("warm-up deadlocked");
+    assert!(matches!(result, Err(DiError::CircularDependency(_))));
+}
@@ -0,0 +1,73 @@
+use crate::core::error_di::DiError;
+use crate::{DIScope, Inject, initialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Default)]
+struct InjectCounter {
+    pub hits: AtomicUsize,
+}
+
+#[rust_di::registry(Scoped, Scoped(name = "audit"))]
+impl InjectCounter {}
+
+struct InjectMissing;
+
+#[rust_di::inject]
+async fn handle(
+    amount: usize,
+    counter: Inject<InjectCounter>,
+    #[inject(name = "audit")] audit: Arc<InjectCounter>,
+    #[inject] missing: Option<Arc<InjectMissing>>,
+) -> Result<usize, DiError> {
+    counter.hits.fetch_add(amount, Ordering::SeqCst);
+    audit.hits.fetch_add(1, Ordering::SeqCst);
+    assert!(missing.is_none());
+    Ok(counter.hits.load(Ordering::SeqCst))
+}
+
+#[rust_di::inject]
+async fn handle_missing(missing: Inject<InjectMissing>) -> Result<(), DiError> {
+    let _ = missing;
+    Ok(())
+}
+
+#[rust_di::with_di_scope]
+async fn handle_in_new_scope(Inject(counter): Inject<InjectCounter>) -> usize {
+    counter.hits.fetch_add(1, Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn test_inject_resolves_parameters_from_current_scope() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        assert_eq!(handle(2).await.unwrap(), 2);
+        assert_eq!(handle(3).await.unwrap(), 5);
+
+        let scope = DIScope::current().unwrap();
+        let audit = scope
+            .clone()
+            .get_by_name::<InjectCounter>("audit")
+            .await
+            .unwrap();
+        assert_eq!(audit.hits.load(Ordering::SeqCst), 2);
+
+        // Новий scope — новий Scoped екземпляр
+        assert_eq!(handle_in_new_scope().await, 0);
+        assert_eq!(handle_in_new_scope().await, 0);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_inject_returns_resolution_errors() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        let err = handle_missing().await.unwrap_err();
+        assert!(matches!(err, DiError::ServiceNotFound(_)));
+    })
+    .await;
+
+    // Поза scope
+    assert!(handle(1).await.is_err());
+}
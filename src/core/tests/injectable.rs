@@ -0,0 +1,61 @@
+use crate::{DIScope, initialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Default)]
+struct InjectableLogger {
+    pub target: &'static str,
+}
+
+#[rust_di::registry(Singleton, Singleton(name = "audit"))]
+impl InjectableLogger {}
+
+struct InjectableUnregistered;
+
+#[derive(rust_di::Injectable)]
+struct InjectableProcessor {
+    logger: Arc<InjectableLogger>,
+    #[inject(name = "audit")]
+    audit: Arc<InjectableLogger>,
+    cache: Option<Arc<InjectableUnregistered>>,
+    #[inject(default)]
+    processed: AtomicUsize,
+    #[inject(with = "processor")]
+    label: &'static str,
+}
+
+#[rust_di::registry(Singleton(factory))]
+impl InjectableProcessor {}
+
+#[derive(rust_di::Injectable)]
+struct InjectableTuple(Arc<InjectableLogger>, #[inject(with = 42)] u32);
+
+#[rust_di::registry(Transient(factory))]
+impl InjectableTuple {}
+
+#[tokio::test]
+async fn test_injectable_derive_resolves_fields() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        let processor = scope.clone().get::<InjectableProcessor>().await.unwrap();
+        let logger = scope.clone().get::<InjectableLogger>().await.unwrap();
+        let audit = scope
+            .clone()
+            .get_by_name::<InjectableLogger>("audit")
+            .await
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&processor.logger, &logger));
+        assert!(Arc::ptr_eq(&processor.audit, &audit));
+        assert!(processor.cache.is_none());
+        assert_eq!(processor.processed.load(Ordering::SeqCst), 0);
+        assert_eq!(processor.label, "processor");
+        assert_eq!(processor.logger.target, "");
+
+        let tuple = scope.get::<InjectableTuple>().await.unwrap();
+        assert!(Arc::ptr_eq(&tuple.0, &logger));
+        assert_eq!(tuple.1, 42);
+    })
+    .await;
+}
@@ -0,0 +1,149 @@
+use crate::DIScope;
+use crate::core::container::{Container, LifetimeConflict, RegistrationInfo};
+use crate::core::contracts::{DuplicatePolicy, Lifetime, LifetimeConflictPolicy};
+use crate::core::error_di::DiError;
+use crate::core::registry::{register_scope, register_singleton, register_transient};
+
+#[derive(Default)]
+struct ConflictClock;
+
+#[rust_di::registry(Singleton)]
+impl ConflictClock {}
+
+struct ConflictCache(&'static str);
+
+fn service_name<T>() -> String {
+    std::any::type_name::<T>().to_string()
+}
+
+#[rust_di::test]
+async fn test_registering_second_lifetime_is_an_error() {
+    let result = register_transient::<ConflictClock, _, _>(|_| async { Ok(ConflictClock) }).await;
+
+    match result {
+        Err(DiError::LifetimeConflict(conflict)) => assert_eq!(
+            conflict,
+            LifetimeConflict {
+                service: service_name::<ConflictClock>(),
+                name: String::new(),
+                existing: Lifetime::Singleton,
+                requested: Lifetime::Transient,
+            }
+        ),
+        other => panic!("expected a lifetime conflict, got {other:?}"),
+    }
+
+    let info = Container::current()
+        .registration::<ConflictClock>("")
+        .unwrap();
+    assert_eq!(info.lifetime, Lifetime::Singleton);
+    assert!(info.shadowed.is_empty());
+}
+
+#[rust_di::test]
+async fn test_lifetime_conflict_policies() {
+    register_transient::<ConflictCache, _, _>(|_| async { Ok(ConflictCache("transient")) })
+        .await
+        .unwrap();
+
+    register_singleton::<ConflictCache, _, _>(|_| async { Ok(ConflictCache("singleton")) })
+        .on_lifetime_conflict(LifetimeConflictPolicy::KeepExisting)
+        .await
+        .unwrap();
+    let container = Container::current();
+    assert_eq!(
+        container
+            .registration::<ConflictCache>("")
+            .unwrap()
+            .lifetime,
+        Lifetime::Transient
+    );
+
+    // Scoped має пріоритет над Transient
+    container.set_lifetime_conflict_policy(LifetimeConflictPolicy::Allow);
+    register_scope::<ConflictCache, _, _>(|_| async { Ok(ConflictCache("scoped")) })
+        .await
+        .unwrap();
+    let info = container.registration::<ConflictCache>("").unwrap();
+    assert_eq!(info.lifetime, Lifetime::Scoped);
+    assert_eq!(info.shadowed, [Lifetime::Transient]);
+    let scope = DIScope::current().unwrap();
+    assert_eq!(
+        scope.clone().get::<ConflictCache>().await.unwrap().0,
+        "scoped"
+    );
+
+    register_singleton::<ConflictCache, _, _>(|_| async { Ok(ConflictCache("singleton")) })
+        .on_lifetime_conflict(LifetimeConflictPolicy::Replace)
+        .await
+        .unwrap();
+    let info = container.registration::<ConflictCache>("").unwrap();
+    assert_eq!(info.lifetime, Lifetime::Singleton);
+    assert!(info.shadowed.is_empty());
+
+    DIScope::run_with_scope(|| async {
+        let cache = DIScope::current()
+            .unwrap()
+            .get::<ConflictCache>()
+            .await
+            .unwrap();
+        assert_eq!(cache.0, "singleton");
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_initialize_reports_inventory_lifetime_conflicts() {
+    let container = Container::new();
+    container
+        .clone()
+        .run(async {
+            register_transient::<ConflictClock, _, _>(|_| async { Ok(ConflictClock) })
+                .await
+                .unwrap();
+
+            for _ in 0..2 {
+                let result = Container::current().initialize().await;
+                assert!(matches!(
+                    result,
+                    Err(DiError::LifetimeConflict(LifetimeConflict {
+                        existing: Lifetime::Transient,
+                        requested: Lifetime::Singleton,
+                        ..
+                    }))
+                ));
+            }
+        })
+        .await;
+}
+
+#[rust_di::test]
+async fn test_registrations_lists_bindings() {
+    for _ in 0..2 {
+        register_scope::<ConflictCache, _, _>(|_| async { Ok(ConflictCache("scoped")) })
+            .policy(DuplicatePolicy::Append)
+            .await
+            .unwrap();
+    }
+
+    let registrations = Container::current().registrations();
+    let cache = registrations
+        .iter()
+        .find(|info| info.service == service_name::<ConflictCache>())
+        .unwrap();
+    assert_eq!(
+        cache,
+        &RegistrationInfo {
+            service: service_name::<ConflictCache>(),
+            name: String::new(),
+            lifetime: Lifetime::Scoped,
+            shadowed: Vec::new(),
+            bindings: 2,
+        }
+    );
+    assert!(
+        registrations
+            .iter()
+            .any(|info| info.service == service_name::<ConflictClock>())
+    );
+}
@@ -0,0 +1,112 @@
+use crate::core::registry::{
+    register_local_scope, register_local_transient, register_local_transient_name,
+    register_singleton,
+};
+use crate::{DIScope, DiError, LocalScope};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// `!Send`: тримає `Rc`
+struct ScriptEngine {
+    globals: Rc<RefCell<Vec<String>>>,
+}
+
+struct Interpreter {
+    engine: Rc<ScriptEngine>,
+    runs: Cell<usize>,
+}
+
+struct Config {
+    prelude: &'static str,
+}
+
+async fn register_services() {
+    register_singleton::<Config, _, _>(|_| async {
+        Ok(Config {
+            prelude: "let x = 1;",
+        })
+    })
+    .await
+    .unwrap();
+    register_local_scope::<ScriptEngine, _, _>(|local| async move {
+        let config = local.get::<Config>().await?;
+        Ok(ScriptEngine {
+            globals: Rc::new(RefCell::new(vec![config.prelude.to_string()])),
+        })
+    })
+    .await
+    .unwrap();
+    register_local_transient::<Interpreter, _, _>(|local| async move {
+        Ok(Interpreter {
+            engine: local.get_local::<ScriptEngine>().await?,
+            runs: Cell::new(0),
+        })
+    })
+    .await
+    .unwrap();
+}
+
+#[rust_di::test]
+async fn test_local_scope_resolves_local_and_send_services() {
+    register_services().await;
+
+    LocalScope::run_with_scope(|| async {
+        let local = LocalScope::current().unwrap();
+        let first = local.get_local::<Interpreter>().await.unwrap();
+        let second = local.get_local::<Interpreter>().await.unwrap();
+        first.runs.set(1);
+
+        assert!(!Rc::ptr_eq(&first, &second));
+        assert_eq!(second.runs.get(), 0);
+        assert!(Rc::ptr_eq(&first.engine, &second.engine));
+        assert_eq!(first.engine.globals.borrow()[0], "let x = 1;");
+
+        let config = local.get::<Config>().await.unwrap();
+        let from_scope = DIScope::current().unwrap().get::<Config>().await.unwrap();
+        assert!(Arc::ptr_eq(&config, &from_scope));
+    })
+    .await;
+}
+
+#[rust_di::test]
+async fn test_each_local_scope_has_its_own_scoped_instances() {
+    register_services().await;
+
+    let engine = || async {
+        LocalScope::current()
+            .unwrap()
+            .get_local::<ScriptEngine>()
+            .await
+            .unwrap()
+    };
+    let first = LocalScope::run_with_scope(engine).await;
+    let second = LocalScope::run_with_scope(engine).await;
+    assert!(!Rc::ptr_eq(&first, &second));
+
+    let local = LocalScope::new(DIScope::current().unwrap());
+    let entered = local.enter(engine()).await;
+    assert!(Rc::ptr_eq(&entered, &local.get_local().await.unwrap()));
+}
+
+#[rust_di::test]
+async fn test_local_scope_reports_missing_and_circular_services() {
+    struct Loop;
+    register_local_transient_name::<Loop, _, _>("loop", |local| async move {
+        local.get_local_by_name::<Loop>("loop").await?;
+        Ok(Loop)
+    })
+    .await
+    .unwrap();
+
+    let local = LocalScope::new(DIScope::current().unwrap());
+    assert!(matches!(
+        local.get_local::<ScriptEngine>().await,
+        Err(DiError::ServiceNotFound(_))
+    ));
+    assert!(matches!(
+        local.get_local_by_name::<Loop>("loop").await,
+        Err(DiError::CircularDependency(_))
+    ));
+    assert!(LocalScope::current().is_err());
+}
@@ -0,0 +1,175 @@
+use crate::core::contracts::Lifetime;
+use crate::core::error_di::DiError;
+use crate::core::metrics::{MetricsSink, live_scopes, set_metrics_sink};
+use crate::core::registry::{register_scope, register_singleton, register_transient};
+use crate::{DIScope, initialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Default, Clone)]
+struct RecordingSink {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl RecordingSink {
+    fn events_for(&self, service: &str) -> Vec<String> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.contains(service))
+            .cloned()
+            .collect()
+    }
+}
+
+impl MetricsSink for RecordingSink {
+    fn resolution(&self, service: &str, _: &str, lifetime: Lifetime) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("resolution {service} {}", lifetime.as_str()));
+    }
+
+    fn resolution_failure(&self, service: &str, name: &str, _: &DiError) {
+        let event = if name.is_empty() {
+            format!("resolution failure {service}")
+        } else {
+            format!("resolution failure {service} {name}")
+        };
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn cache_hit(&self, service: &str, _: &str, lifetime: Lifetime) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("hit {service} {}", lifetime.as_str()));
+    }
+
+    fn cache_miss(&self, service: &str, _: &str, lifetime: Lifetime) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("miss {service} {}", lifetime.as_str()));
+    }
+
+    fn factory_latency(&self, service: &str, _: &str, lifetime: Lifetime, _: Duration) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("latency {service} {}", lifetime.as_str()));
+    }
+
+    fn factory_failure(&self, service: &str, _: &str, lifetime: Lifetime, _: &DiError) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("failure {service} {}", lifetime.as_str()));
+    }
+}
+
+struct MetricsSingletonService;
+struct MetricsScopedService;
+struct MetricsTransientService;
+struct MetricsFailingService;
+struct MetricsUnregisteredService;
+
+#[tokio::test]
+async fn test_metrics_sink_receives_resolution_events() {
+    initialize().await;
+    let sink = RecordingSink::default();
+    set_metrics_sink(sink.clone());
+
+    register_singleton::<MetricsSingletonService, _, _>(|_| async { Ok(MetricsSingletonService) })
+        .await
+        .unwrap();
+    register_scope::<MetricsScopedService, _, _>(|_| async { Ok(MetricsScopedService) })
+        .await
+        .unwrap();
+    register_transient::<MetricsTransientService, _, _>(|_| async { Ok(MetricsTransientService) })
+        .await
+        .unwrap();
+    register_transient::<MetricsFailingService, _, _>(|_| async {
+        Err::<MetricsFailingService, _>(DiError::ServiceNotFound("dependency".to_string()))
+    })
+    .await
+    .unwrap();
+
+    DIScope::run_with_scope(|| async {
+        assert!(live_scopes() >= 1);
+
+        let scope = DIScope::current().unwrap();
+        for _ in 0..2 {
+            scope
+                .clone()
+                .get::<MetricsSingletonService>()
+                .await
+                .unwrap();
+            scope.clone().get::<MetricsScopedService>().await.unwrap();
+            scope
+                .clone()
+                .get::<MetricsTransientService>()
+                .await
+                .unwrap();
+        }
+        assert!(scope.clone().get::<MetricsFailingService>().await.is_err());
+        assert!(
+            scope
+                .clone()
+                .get::<MetricsUnregisteredService>()
+                .await
+                .is_err()
+        );
+        assert!(
+            scope
+                .get_by_name::<MetricsScopedService>("never-registered-metrics-name")
+                .await
+                .is_err()
+        );
+    })
+    .await;
+
+    assert_eq!(
+        sink.events_for("MetricsSingletonService"),
+        vec![
+            "latency rust_di::core::tests::metrics::MetricsSingletonService singleton",
+            "resolution rust_di::core::tests::metrics::MetricsSingletonService singleton",
+            "miss rust_di::core::tests::metrics::MetricsSingletonService singleton",
+            "resolution rust_di::core::tests::metrics::MetricsSingletonService singleton",
+            "hit rust_di::core::tests::metrics::MetricsSingletonService singleton",
+        ]
+    );
+    assert_eq!(
+        sink.events_for("MetricsScopedService")
+            .iter()
+            .filter(|event| event.starts_with("hit"))
+            .count(),
+        1
+    );
+    assert_eq!(
+        sink.events_for("MetricsTransientService")
+            .iter()
+            .filter(|event| event.starts_with("latency"))
+            .count(),
+        2
+    );
+    assert_eq!(
+        sink.events_for("MetricsFailingService"),
+        vec![
+            "latency rust_di::core::tests::metrics::MetricsFailingService transient",
+            "failure rust_di::core::tests::metrics::MetricsFailingService transient",
+            "resolution failure rust_di::core::tests::metrics::MetricsFailingService",
+        ]
+    );
+    assert_eq!(
+        sink.events_for("MetricsUnregisteredService"),
+        vec!["resolution failure rust_di::core::tests::metrics::MetricsUnregisteredService"]
+    );
+    assert_eq!(
+        sink.events_for("never-registered-metrics-name"),
+        vec![
+            "resolution failure rust_di::core::tests::metrics::MetricsScopedService never-registered-metrics-name"
+        ]
+    );
+}
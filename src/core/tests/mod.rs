@@ -1,3 +1,29 @@
+mod context;
+mod duplicate_policy;
+mod factory_fn;
+mod factory_timeout;
+mod fallback;
+mod future_ext;
+mod init_concurrency;
+mod inject;
+mod injectable;
+mod lifetime_conflict;
+mod local_scope;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod override_registration;
+mod profiling;
+#[cfg(feature = "rayon")]
+mod rayon;
+mod registration_order;
+mod registry_generic;
+mod registry_open_generic;
 mod registry_scoped;
 mod registry_singleton;
 mod registry_transient;
+mod retry;
+mod service_key;
+#[cfg(feature = "tokio")]
+mod spawn;
+mod sync_scope;
+mod test_container;
@@ -0,0 +1,173 @@
+use crate::DIScope;
+use crate::core::container::Container;
+use crate::core::contracts::Lifetime;
+use crate::core::error_di::DiError;
+use crate::core::registry::{
+    override_scope_name, override_singleton, override_transient, register_singleton,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+struct OverrideSender(&'static str);
+
+impl Default for OverrideSender {
+    fn default() -> Self {
+        Self("smtp")
+    }
+}
+
+#[rust_di::registry(Singleton, Scoped(name = "per_request"))]
+impl OverrideSender {}
+
+struct OverrideClock(u64);
+
+async fn sender_name() -> &'static str {
+    DIScope::current()
+        .unwrap()
+        .get::<OverrideSender>()
+        .await
+        .unwrap()
+        .0
+}
+
+#[rust_di::test]
+async fn test_override_singleton_evicts_cache_and_restores_on_drop() {
+    let real = DIScope::current()
+        .unwrap()
+        .get::<OverrideSender>()
+        .await
+        .unwrap();
+    assert_eq!(real.0, "smtp");
+
+    {
+        let _guard =
+            override_singleton::<OverrideSender, _, _>(|_| async { Ok(OverrideSender("fake")) })
+                .await;
+        assert_eq!(sender_name().await, "fake");
+    }
+
+    // Повертається попередня фабрика, а кеш з fake вже видалений
+    let restored = DIScope::current()
+        .unwrap()
+        .get::<OverrideSender>()
+        .await
+        .unwrap();
+    assert_eq!(restored.0, "smtp");
+    assert!(!Arc::ptr_eq(&real, &restored));
+}
+
+#[rust_di::test]
+async fn test_override_adds_missing_registration_and_removes_it_on_drop() {
+    let guard =
+        override_transient::<OverrideClock, _, _>(|_| async { Ok(OverrideClock(42)) }).await;
+    let scope = DIScope::current().unwrap();
+    assert_eq!(scope.clone().get::<OverrideClock>().await.unwrap().0, 42);
+
+    drop(guard);
+    assert!(scope.try_get::<OverrideClock>().await.unwrap().is_none());
+}
+
+#[rust_di::test]
+async fn test_override_persist_keeps_new_factory() {
+    override_scope_name::<OverrideSender, _, _>("per_request", |_| async {
+        Ok(OverrideSender("patched"))
+    })
+    .await
+    .persist();
+
+    DIScope::run_with_scope(|| async {
+        let sender = DIScope::current()
+            .unwrap()
+            .get_by_name::<OverrideSender>("per_request")
+            .await
+            .unwrap();
+        assert_eq!(sender.0, "patched");
+    })
+    .await;
+}
+
+#[rust_di::test]
+async fn test_container_replace_nested_guards_restore_in_order() {
+    let container = Container::current();
+    let outer = container.replace::<OverrideSender, _, _>(Lifetime::Singleton, "", |_| async {
+        Ok(OverrideSender("outer"))
+    });
+    let inner = container.replace::<OverrideSender, _, _>(Lifetime::Singleton, "", |_| async {
+        Ok(OverrideSender("inner"))
+    });
+    assert_eq!(sender_name().await, "inner");
+
+    drop(inner);
+    assert_eq!(sender_name().await, "outer");
+
+    drop(outer);
+    assert_eq!(sender_name().await, "smtp");
+}
+
+#[rust_di::test]
+async fn test_container_replace_guards_restore_in_any_drop_order() {
+    let container = Container::current();
+    let outer = container.replace::<OverrideSender, _, _>(Lifetime::Singleton, "", |_| async {
+        Ok(OverrideSender("outer"))
+    });
+    let inner = container.replace::<OverrideSender, _, _>(Lifetime::Singleton, "", |_| async {
+        Ok(OverrideSender("inner"))
+    });
+
+    drop(outer);
+    assert_eq!(sender_name().await, "inner");
+
+    drop(inner);
+    assert_eq!(sender_name().await, "smtp");
+}
+
+#[rust_di::test]
+async fn test_override_hides_other_lifetimes_of_the_key() {
+    let guard =
+        override_transient::<OverrideSender, _, _>(|_| async { Ok(OverrideSender("fake")) }).await;
+    assert_eq!(sender_name().await, "fake");
+    assert_eq!(
+        Container::current()
+            .registration::<OverrideSender>("")
+            .unwrap()
+            .lifetime,
+        Lifetime::Transient
+    );
+
+    drop(guard);
+    assert_eq!(sender_name().await, "smtp");
+}
+
+#[rust_di::test]
+async fn test_override_uses_container_factory_timeout() {
+    Container::current().set_factory_timeout(Some(Duration::from_millis(20)));
+    let _guard = override_singleton::<OverrideSender, _, _>(|_| async {
+        futures_timer::Delay::new(Duration::from_secs(5)).await;
+        Ok(OverrideSender("slow"))
+    })
+    .await;
+
+    let result = DIScope::current().unwrap().get::<OverrideSender>().await;
+    assert!(matches!(result, Err(DiError::FactoryTimeout { .. })));
+}
+
+struct SlowSender(&'static str);
+
+#[rust_di::test]
+async fn test_override_is_not_undone_by_a_construction_in_flight() {
+    register_singleton::<SlowSender, _, _>(|_| async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Ok(SlowSender("real"))
+    })
+    .await
+    .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    let (in_flight, _guard) = futures::join!(scope.clone().get::<SlowSender>(), async {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        override_singleton::<SlowSender, _, _>(|_| async { Ok(SlowSender("fake")) }).await
+    });
+
+    assert_eq!(in_flight.unwrap().0, "real");
+    assert_eq!(scope.get::<SlowSender>().await.unwrap().0, "fake");
+}
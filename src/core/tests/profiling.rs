@@ -0,0 +1,56 @@
+use crate::core::profiling::{finish_startup_profiling, start_startup_profiling};
+use crate::core::registry::register_singleton;
+use crate::{DIScope, initialize};
+use std::time::Duration;
+
+struct ProfiledPool;
+struct ProfiledRepository;
+
+#[tokio::test]
+async fn test_startup_report_separates_self_and_total_time() {
+    initialize().await;
+
+    register_singleton::<ProfiledPool, _, _>(|_| async {
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        Ok(ProfiledPool)
+    })
+    .await
+    .unwrap();
+
+    register_singleton::<ProfiledRepository, _, _>(|scope| async move {
+        scope.get::<ProfiledPool>().await?;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        Ok(ProfiledRepository)
+    })
+    .await
+    .unwrap();
+
+    start_startup_profiling();
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        scope.get::<ProfiledRepository>().await.unwrap();
+    })
+    .await;
+    let report = finish_startup_profiling();
+
+    let ours: Vec<_> = report
+        .singletons
+        .iter()
+        .filter(|timing| timing.service.contains("Profiled"))
+        .collect();
+    assert_eq!(ours.len(), 2);
+
+    // Відсортовано за загальним часом: репозиторій включає час пулу
+    let repository = ours[0];
+    let pool = ours[1];
+    assert!(repository.service.ends_with("ProfiledRepository"));
+    assert!(pool.service.ends_with("ProfiledPool"));
+    assert!(repository.total >= pool.total);
+    assert!(repository.self_time < repository.total);
+    assert!(repository.self_time >= Duration::from_millis(10));
+    assert!(pool.self_time >= Duration::from_millis(30));
+
+    let table = report.to_string();
+    assert!(table.starts_with("Singleton"));
+    assert!(table.contains("ProfiledRepository"));
+}
@@ -0,0 +1,47 @@
+use crate::DIScope;
+use crate::core::rayon::DiParallelIteratorExt;
+use crate::core::registry::{register_scope, register_transient};
+use rayon::prelude::*;
+use std::sync::Arc;
+
+struct Session;
+
+struct Pricing {
+    rate: u64,
+}
+
+#[rust_di::test]
+async fn test_parallel_iterators_resolve_from_the_scope() {
+    register_scope::<Session, _, _>(|_| async { Ok(Session) })
+        .await
+        .unwrap();
+    register_transient::<Pricing, _, _>(|_| async { Ok(Pricing { rate: 2 }) })
+        .await
+        .unwrap();
+    let scope = DIScope::current().unwrap();
+    let session = scope.clone().get::<Session>().await.unwrap();
+
+    let totals: Vec<u64> = (0..1000u64)
+        .into_par_iter()
+        .with_di_scope(scope.clone())
+        .map(|amount| {
+            let pricing = DIScope::current().unwrap().get_sync::<Pricing>().unwrap();
+            amount * pricing.rate
+        })
+        .collect();
+    assert_eq!(
+        totals,
+        (0..1000u64).map(|amount| amount * 2).collect::<Vec<_>>()
+    );
+
+    let all_shared = (0..1000)
+        .into_par_iter()
+        .enumerate()
+        .with_di_scope(scope.clone())
+        .filter(|(index, _)| index % 2 == 0)
+        .all(|_| {
+            let current = DIScope::current().unwrap();
+            Arc::ptr_eq(&current.get_sync::<Session>().unwrap(), &session)
+        });
+    assert!(all_shared);
+}
@@ -0,0 +1,67 @@
+use crate::DIScope;
+use crate::core::di_inventory::DiConstructor;
+use crate::core::error_di::DiError;
+use crate::core::factory::DiFactory;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+struct OrderedGreeting(&'static str);
+
+impl Default for OrderedGreeting {
+    fn default() -> Self {
+        Self("default")
+    }
+}
+
+#[async_trait]
+impl DiFactory for OrderedGreeting {
+    async fn create(_scope: Arc<DIScope>) -> Result<Self, DiError> {
+        Ok(Self("factory"))
+    }
+}
+
+// Оголошена першою, але з нижчим пріоритетом
+#[rust_di::registry(Singleton, priority = -1)]
+impl OrderedGreeting {}
+
+#[rust_di::registry(Singleton(factory), priority = 10)]
+impl OrderedGreeting {}
+
+#[derive(Default)]
+struct AuditSink;
+
+#[derive(Default)]
+struct ConsoleSink;
+
+#[rust_di::registry(Singleton, after = ConsoleSink)]
+impl AuditSink {}
+
+#[rust_di::registry(Singleton)]
+impl ConsoleSink {}
+
+fn position<T: 'static>(ordered: &[&DiConstructor]) -> usize {
+    ordered
+        .iter()
+        .position(|ctor| (ctor.service)() == std::any::TypeId::of::<T>())
+        .unwrap()
+}
+
+#[rust_di::test]
+async fn test_higher_priority_registration_wins() {
+    let scope = DIScope::current().unwrap();
+    let greeting = scope.get::<OrderedGreeting>().await.unwrap();
+    assert_eq!(greeting.0, "factory");
+}
+
+#[test]
+fn test_registrations_are_ordered_by_priority_then_after() {
+    let ordered = DiConstructor::ordered();
+
+    let priorities: Vec<i32> = ordered
+        .iter()
+        .filter(|ctor| ctor.after.is_none())
+        .map(|ctor| ctor.priority)
+        .collect();
+    assert!(priorities.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(position::<AuditSink>(&ordered) > position::<ConsoleSink>(&ordered));
+}
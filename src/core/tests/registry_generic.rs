@@ -0,0 +1,105 @@
+use crate::core::error_di::DiError;
+use crate::core::factory::DiFactory;
+use crate::{DIScope, initialize};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+struct GenericUser;
+struct GenericOrder;
+
+trait GenericEntity: Send + Sync + 'static {
+    const TABLE: &'static str;
+}
+
+impl GenericEntity for GenericUser {
+    const TABLE: &'static str = "users";
+}
+
+impl GenericEntity for GenericOrder {
+    const TABLE: &'static str = "orders";
+}
+
+struct GenericRepo<T> {
+    pub table: &'static str,
+    _entity: PhantomData<T>,
+}
+
+impl<T: GenericEntity> Default for GenericRepo<T> {
+    fn default() -> Self {
+        GenericRepo {
+            table: T::TABLE,
+            _entity: PhantomData,
+        }
+    }
+}
+
+#[rust_di::registry(Singleton, for = [GenericRepo<GenericUser>, GenericRepo<GenericOrder>])]
+impl<T: GenericEntity> GenericRepo<T> {}
+
+#[tokio::test]
+async fn test_generic_default_registration_for_each_instantiation() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        let users = scope
+            .clone()
+            .get::<GenericRepo<GenericUser>>()
+            .await
+            .unwrap();
+        let orders = scope.get::<GenericRepo<GenericOrder>>().await.unwrap();
+        assert_eq!(users.table, "users");
+        assert_eq!(orders.table, "orders");
+    })
+    .await;
+}
+
+struct GenericService<T> {
+    pub table: &'static str,
+    _entity: PhantomData<T>,
+}
+
+#[async_trait::async_trait]
+impl<T: GenericEntity> DiFactory for GenericService<T> {
+    async fn create(scope: Arc<DIScope>) -> Result<Self, DiError> {
+        let repo = scope.get::<GenericRepo<T>>().await?;
+        Ok(GenericService {
+            table: repo.table,
+            _entity: PhantomData,
+        })
+    }
+}
+
+#[rust_di::registry(
+    Transient(factory),
+    Scoped(factory, name = "scoped"),
+    for = [GenericService<GenericOrder>]
+)]
+impl<T: GenericEntity> GenericService<T> {}
+
+#[tokio::test]
+async fn test_generic_factory_registration() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        let transient = scope
+            .clone()
+            .get::<GenericService<GenericOrder>>()
+            .await
+            .unwrap();
+        let scoped = scope
+            .clone()
+            .get_by_name::<GenericService<GenericOrder>>("scoped")
+            .await
+            .unwrap();
+        assert_eq!(transient.table, "orders");
+        assert_eq!(scoped.table, "orders");
+        assert!(
+            scope
+                .try_get::<GenericService<GenericUser>>()
+                .await
+                .unwrap()
+                .is_none()
+        );
+    })
+    .await;
+}
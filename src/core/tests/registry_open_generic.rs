@@ -0,0 +1,239 @@
+use crate::core::error_di::DiError;
+use crate::core::factory::DiFactory;
+use crate::core::open_generic::{OpenGeneric, TypeFamily};
+use crate::core::registry::register_open_transient;
+use crate::{DIScope, initialize};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+trait OpenEntity: Send + Sync + 'static {
+    const TABLE: &'static str;
+}
+
+struct OpenInvoice;
+struct OpenCustomer;
+
+impl OpenEntity for OpenInvoice {
+    const TABLE: &'static str = "invoices";
+}
+
+impl OpenEntity for OpenCustomer {
+    const TABLE: &'static str = "customers";
+}
+
+static OPEN_REPOS_BUILT: AtomicUsize = AtomicUsize::new(0);
+
+struct OpenRepo<T> {
+    pub table: &'static str,
+    _entity: PhantomData<T>,
+}
+
+#[async_trait::async_trait]
+impl<T: OpenEntity> DiFactory for OpenRepo<T> {
+    async fn create(_: Arc<DIScope>) -> Result<Self, DiError> {
+        OPEN_REPOS_BUILT.fetch_add(1, Ordering::SeqCst);
+        Ok(OpenRepo {
+            table: T::TABLE,
+            _entity: PhantomData,
+        })
+    }
+}
+
+#[rust_di::registry(Singleton(factory), open)]
+impl<T: OpenEntity> OpenRepo<T> {}
+
+#[tokio::test]
+async fn test_open_generic_singleton_is_built_per_instantiation() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        let invoices = scope
+            .clone()
+            .get_open::<OpenRepo<OpenInvoice>>()
+            .await
+            .unwrap();
+        let again = scope
+            .clone()
+            .get_open::<OpenRepo<OpenInvoice>>()
+            .await
+            .unwrap();
+        let customers = scope
+            .clone()
+            .get_open::<OpenRepo<OpenCustomer>>()
+            .await
+            .unwrap();
+
+        assert_eq!(invoices.table, "invoices");
+        assert_eq!(customers.table, "customers");
+        assert!(Arc::ptr_eq(&invoices, &again));
+        assert_eq!(OPEN_REPOS_BUILT.load(Ordering::SeqCst), 2);
+
+        // Після матеріалізації тип доступний і через звичайний `get`
+        let plain = scope.get::<OpenRepo<OpenInvoice>>().await.unwrap();
+        assert!(Arc::ptr_eq(&invoices, &plain));
+    })
+    .await;
+}
+
+/// `T: OpenEntity` on the struct itself: `BoundedRepo<()>` is not a valid type.
+struct BoundedRepo<T: OpenEntity>(PhantomData<T>);
+
+#[async_trait::async_trait]
+impl<T: OpenEntity> DiFactory for BoundedRepo<T> {
+    async fn create(_: Arc<DIScope>) -> Result<Self, DiError> {
+        Ok(BoundedRepo(PhantomData))
+    }
+}
+
+#[rust_di::registry(Transient(factory), open)]
+impl<T: OpenEntity> BoundedRepo<T> {}
+
+#[tokio::test]
+async fn test_open_generic_struct_with_bounded_parameters() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        assert!(
+            scope
+                .clone()
+                .get_open::<BoundedRepo<OpenInvoice>>()
+                .await
+                .is_ok()
+        );
+        assert_eq!(
+            TypeFamily::of::<BoundedRepo<OpenInvoice>>(),
+            TypeFamily::of::<BoundedRepo<OpenCustomer>>()
+        );
+    })
+    .await;
+}
+
+struct OpenCache<T> {
+    _entity: PhantomData<T>,
+}
+
+impl<T> Default for OpenCache<T> {
+    fn default() -> Self {
+        OpenCache {
+            _entity: PhantomData,
+        }
+    }
+}
+
+#[rust_di::registry(Scoped(name = "cache"), open)]
+impl<T: OpenEntity> OpenCache<T> {}
+
+#[derive(rust_di::Injectable)]
+struct OpenInvoiceService {
+    #[inject(open)]
+    repo: Arc<OpenRepo<OpenInvoice>>,
+    #[inject(open, name = "cache")]
+    cache: Arc<OpenCache<OpenInvoice>>,
+}
+
+#[rust_di::registry(Transient(factory))]
+impl OpenInvoiceService {}
+
+#[tokio::test]
+async fn test_open_generic_default_members_and_injectable_fields() {
+    initialize().await;
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        let service = scope.clone().get::<OpenInvoiceService>().await.unwrap();
+        let cache = scope
+            .get_open_by_name::<OpenCache<OpenInvoice>>("cache")
+            .await
+            .unwrap();
+
+        assert_eq!(service.repo.table, "invoices");
+        assert!(Arc::ptr_eq(&service.cache, &cache));
+    })
+    .await;
+}
+
+struct OpenUnregistered<T>(PhantomData<T>);
+
+impl<T> OpenGeneric for OpenUnregistered<T> {
+    type Family = OpenUnregistered<()>;
+}
+
+#[async_trait::async_trait]
+impl<T: Send + Sync + 'static> DiFactory for OpenUnregistered<T> {
+    async fn create(_: Arc<DIScope>) -> Result<Self, DiError> {
+        Ok(OpenUnregistered(PhantomData))
+    }
+}
+
+#[tokio::test]
+async fn test_open_generic_requires_family_registration() {
+    initialize().await;
+    assert_eq!(
+        TypeFamily::token::<OpenUnregistered<()>>(),
+        TypeFamily::of::<OpenUnregistered<OpenInvoice>>()
+    );
+
+    DIScope::run_with_scope(|| async {
+        let scope = DIScope::current().unwrap();
+        let missing = scope
+            .clone()
+            .get_open::<OpenUnregistered<OpenInvoice>>()
+            .await;
+        assert!(matches!(missing, Err(DiError::ServiceNotFound(_))));
+
+        register_open_transient::<OpenUnregistered<()>>()
+            .await
+            .unwrap();
+        let first = scope
+            .clone()
+            .get_open::<OpenUnregistered<OpenInvoice>>()
+            .await
+            .unwrap();
+        let second = scope
+            .get_open::<OpenUnregistered<OpenInvoice>>()
+            .await
+            .unwrap();
+        assert!(!Arc::ptr_eq(&first, &second));
+    })
+    .await;
+}
+
+/// Both `Repo`s are named `...::test_open_generic_families_with_the_same_name::Repo`.
+macro_rules! block_repo {
+    ($table:literal) => {{
+        struct Repo<T>(&'static str, PhantomData<T>);
+
+        impl<T> OpenGeneric for Repo<T> {
+            type Family = Repo<()>;
+        }
+
+        #[async_trait::async_trait]
+        impl<T: Send + Sync + 'static> DiFactory for Repo<T> {
+            async fn create(_: Arc<DIScope>) -> Result<Self, DiError> {
+                Ok(Repo($table, PhantomData))
+            }
+        }
+
+        register_open_transient::<Repo<()>>().await.unwrap();
+        (
+            TypeFamily::of::<Repo<OpenInvoice>>(),
+            DIScope::current()
+                .unwrap()
+                .get_open::<Repo<OpenInvoice>>()
+                .await
+                .unwrap()
+                .0,
+        )
+    }};
+}
+
+#[rust_di::test]
+async fn test_open_generic_families_with_the_same_name() {
+    let (invoices, invoices_table) = block_repo!("invoices");
+    let (archive, archive_table) = block_repo!("archive");
+
+    assert_eq!(invoices.name(), archive.name());
+    assert_ne!(invoices, archive);
+    assert_eq!(invoices_table, "invoices");
+    assert_eq!(archive_table, "archive");
+}
@@ -1,8 +1,11 @@
+use crate::core::container::Container;
+use crate::core::contracts::ServiceKey;
 use crate::initialize;
 use rust_di::DIScope;
 use rust_di::DiError;
 use rust_di::core::factory::DiFactory;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::sync::Mutex;
 
 struct SimpleService {
@@ -220,3 +223,38 @@ async fn test_multiple_named_instances_are_distinct() {
     })
     .await;
 }
+
+static EAGER_CONSTRUCTED: AtomicUsize = AtomicUsize::new(0);
+
+struct EagerService;
+
+impl Default for EagerService {
+    fn default() -> Self {
+        EAGER_CONSTRUCTED.fetch_add(1, Ordering::SeqCst);
+        EagerService
+    }
+}
+
+#[rust_di::registry(Singleton(eager))]
+impl EagerService {}
+
+// Кожен `#[rust_di::test]` контейнер теж будує eager Singleton,
+// тож перевіряємо кеш цього контейнера, а не глобальний лічильник
+#[rust_di::test]
+async fn test_singleton_eager_registration_is_built_by_initialize() {
+    let key = ServiceKey::of::<EagerService>("");
+    let cached = Container::current()
+        .singleton_cache
+        .get(&key)
+        .map(|entry| entry.value().clone())
+        .expect("eager Singleton was not built by initialize()");
+    assert!(EAGER_CONSTRUCTED.load(Ordering::SeqCst) >= 1);
+
+    let scope = DIScope::current().unwrap();
+    let resolved = scope.get::<EagerService>().await.unwrap();
+    let cached: Arc<dyn std::any::Any + Send + Sync> = cached;
+    assert!(Arc::ptr_eq(
+        &cached.downcast::<EagerService>().unwrap(),
+        &resolved
+    ));
+}
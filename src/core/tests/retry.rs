@@ -0,0 +1,161 @@
+use crate::DIScope;
+use crate::core::container::Container;
+use crate::core::contracts::ServiceKey;
+use crate::core::error_di::DiError;
+use crate::core::factory::DiFactory;
+use crate::core::registry::{override_singleton, register_singleton, register_transient};
+use crate::core::retry::RetryPolicy;
+use async_trait::async_trait;
+use futures_timer::Delay;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+struct Database;
+
+fn not_ready() -> DiError {
+    DiError::ServiceNotFound("database".to_string())
+}
+
+/// Factory failing the first `failures` calls.
+fn flaky(
+    calls: Arc<AtomicU32>,
+    failures: u32,
+) -> impl Fn(Arc<DIScope>) -> futures::future::Ready<Result<Database, DiError>> {
+    move |_| {
+        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        futures::future::ready(if call > failures {
+            Ok(Database)
+        } else {
+            Err(not_ready())
+        })
+    }
+}
+
+fn quick(attempts: u32) -> RetryPolicy {
+    RetryPolicy::new(attempts).backoff(Duration::from_millis(1))
+}
+
+static WARMING_CALLS: AtomicU32 = AtomicU32::new(0);
+
+struct WarmingCache;
+
+#[async_trait]
+impl DiFactory for WarmingCache {
+    async fn create(_scope: Arc<DIScope>) -> Result<Self, DiError> {
+        if WARMING_CALLS.fetch_add(1, Ordering::SeqCst) < 2 {
+            return Err(not_ready());
+        }
+        Ok(WarmingCache)
+    }
+}
+
+#[rust_di::registry(Singleton(factory, retry(attempts = 3, backoff = "1ms", jitter = 0.5)))]
+impl WarmingCache {}
+
+#[rust_di::test]
+async fn test_registry_retry_recovers_from_failures() {
+    let scope = DIScope::current().unwrap();
+    assert!(scope.get::<WarmingCache>().await.is_ok());
+    assert_eq!(WARMING_CALLS.load(Ordering::SeqCst), 3);
+}
+
+#[rust_di::test]
+async fn test_exhausted_retries_report_attempts_and_cause() {
+    let calls = Arc::new(AtomicU32::new(0));
+    register_singleton::<Database, _, _>(flaky(calls.clone(), u32::MAX))
+        .with_retry(quick(3))
+        .await
+        .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    match scope.clone().get::<Database>().await {
+        Err(DiError::FactoryFailed {
+            key,
+            attempts,
+            cause,
+        }) => {
+            assert_eq!(key, ServiceKey::of::<Database>(""));
+            assert_eq!(attempts, 3);
+            assert!(matches!(*cause, DiError::ServiceNotFound(_)));
+        }
+        other => panic!("expected exhausted retries, got {:?}", other.map(|_| ())),
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    // Без cool-down наступний get пробує знову
+    assert!(scope.get::<Database>().await.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+}
+
+#[rust_di::test]
+async fn test_cached_failure_is_shared_until_cooldown_ends() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let slow_calls = calls.clone();
+    register_singleton::<Database, _, _>(move |_| {
+        slow_calls.fetch_add(1, Ordering::SeqCst);
+        async {
+            Delay::new(Duration::from_millis(20)).await;
+            Err::<Database, _>(not_ready())
+        }
+    })
+    .with_retry(RetryPolicy::new(1).cache_failure_for(Duration::from_secs(60)))
+    .await
+    .unwrap();
+
+    let results = futures::future::join_all((0..5).map(|_| {
+        DIScope::run_with_scope(|| async { DIScope::current()?.get::<Database>().await })
+    }))
+    .await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(
+        results
+            .iter()
+            .all(|result| matches!(result, Err(DiError::FactoryFailed { attempts: 1, .. })))
+    );
+}
+
+#[rust_di::test]
+async fn test_retried_singleton_is_rebuilt_after_eviction() {
+    let calls = Arc::new(AtomicU32::new(0));
+    register_singleton::<Database, _, _>(flaky(calls.clone(), 0))
+        .with_retry(quick(3))
+        .await
+        .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    let first = scope.clone().get::<Database>().await.unwrap();
+    drop(override_singleton::<Database, _, _>(|_| async { Ok(Database) }).await);
+    let second = scope.get::<Database>().await.unwrap();
+
+    assert!(!Arc::ptr_eq(&first, &second));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[rust_di::test]
+async fn test_retry_is_rejected_for_transients() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let result = register_transient::<Database, _, _>(flaky(calls.clone(), 1))
+        .with_retry(quick(3))
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(DiError::InvalidRegistration { key, .. }) if key == ServiceKey::of::<Database>("")
+    ));
+    assert!(Container::current().registration::<Database>("").is_none());
+}
+
+#[test]
+fn test_backoff_grows_exponentially_up_to_max() {
+    let policy = RetryPolicy::new(5)
+        .backoff(Duration::from_millis(100))
+        .max_backoff(Duration::from_millis(300));
+
+    assert_eq!(policy.delay(1), Duration::from_millis(100));
+    assert_eq!(policy.delay(2), Duration::from_millis(200));
+    assert_eq!(policy.delay(3), Duration::from_millis(300));
+    assert_eq!(policy.delay(10), Duration::from_millis(300));
+    assert_eq!(RetryPolicy::new(0).max_attempts(), 1);
+}
@@ -0,0 +1,113 @@
+use crate::core::contracts::ServiceKey;
+use crate::core::registry::{register_transient, register_transient_name};
+use crate::{DIScope, DiError};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+mod v1 {
+    pub struct Config(pub u8);
+}
+
+fn key_of<T: 'static>(_: &T, name: &str) -> ServiceKey {
+    ServiceKey::of::<T>(name)
+}
+
+fn type_name_of<T>(_: &T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
+async fn register_copy<T: Copy + Send + Sync + 'static>(value: T) {
+    register_transient::<T, _, _>(move |_| async move { Ok(value) })
+        .await
+        .unwrap();
+}
+
+async fn get_like<T: Send + Sync + 'static>(_: &T) -> Arc<T> {
+    DIScope::current().unwrap().get::<T>().await.unwrap()
+}
+
+#[test]
+fn test_service_key_identity_uses_type_id_and_name() {
+    // Обидва замикання мають однаковий `type_name`, але різні `TypeId`
+    let first = || 1u8;
+    let second = || 2u8;
+    assert_eq!(type_name_of(&first), type_name_of(&second));
+
+    let key = key_of(&first, "primary");
+    assert_eq!(key, key_of(&first, "primary"));
+    assert_ne!(key, key_of(&second, "primary"));
+    assert_ne!(key, key_of(&first, "replica"));
+    assert_ne!(key, key.with_binding(1));
+
+    assert_eq!(
+        ServiceKey::of::<v1::Config>("").type_id(),
+        std::any::TypeId::of::<v1::Config>()
+    );
+    assert_eq!(key.type_name(), type_name_of(&first));
+    assert_eq!(key.name(), "primary");
+
+    let keys: HashSet<_> = [key, key_of(&first, "primary"), key_of(&second, "primary")]
+        .into_iter()
+        .collect();
+    assert_eq!(keys.len(), 2);
+}
+
+#[rust_di::test]
+async fn test_types_with_the_same_name_resolve_independently() {
+    let first = || 1u8;
+    let second = || 2u8;
+    assert_eq!(type_name_of(&first), type_name_of(&second));
+
+    register_copy(first).await;
+    register_copy(second).await;
+
+    assert_eq!((get_like(&first).await)(), 1);
+    assert_eq!((get_like(&second).await)(), 2);
+}
+
+#[rust_di::test]
+async fn test_unknown_name_is_not_found_without_registering_it() {
+    register_transient::<v1::Config, _, _>(|_| async { Ok(v1::Config(1)) })
+        .await
+        .unwrap();
+
+    let name = "never-registered-name";
+    assert!(ServiceKey::lookup::<v1::Config>(name).is_none());
+
+    let scope = DIScope::current().unwrap();
+    let result = scope.clone().get_by_name::<v1::Config>(name).await;
+    assert!(matches!(result, Err(DiError::ServiceNotFound(ref missing)) if missing == name));
+    assert!(
+        scope
+            .clone()
+            .try_get_by_name::<v1::Config>(name)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        scope
+            .get_all_by_name::<v1::Config>(name)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(ServiceKey::lookup::<v1::Config>(name).is_none());
+}
+
+#[rust_di::test]
+async fn test_named_service_may_depend_on_same_type() {
+    register_transient::<v1::Config, _, _>(|_| async { Ok(v1::Config(1)) })
+        .await
+        .unwrap();
+    register_transient_name::<v1::Config, _, _>("derived", |scope| async move {
+        let base = scope.get::<v1::Config>().await?;
+        Ok(v1::Config(base.0 + 1))
+    })
+    .await
+    .unwrap();
+
+    let scope = DIScope::current().unwrap();
+    let derived = scope.get_by_name::<v1::Config>("derived").await.unwrap();
+    assert_eq!(derived.0, 2);
+}
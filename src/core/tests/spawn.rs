@@ -0,0 +1,113 @@
+use crate::core::container::Container;
+use crate::core::registry::register_scope;
+use crate::{DIScope, ScopeMode, ScopedJoinSet};
+use std::sync::Arc;
+use tokio::task::JoinSet;
+
+struct RequestId;
+
+async fn register_request_id() {
+    register_scope::<RequestId, _, _>(|_| async { Ok(RequestId) })
+        .await
+        .unwrap();
+}
+
+struct Counter;
+
+#[rust_di::test]
+async fn test_spawn_shares_the_parent_scope() {
+    register_request_id().await;
+    register_scope::<Counter, _, _>(|_| async { Ok(Counter) })
+        .await
+        .unwrap();
+    let scope = DIScope::current().unwrap();
+    let parent = scope.clone().get::<RequestId>().await.unwrap();
+
+    let (child, counter) = crate::spawn(async {
+        let scope = DIScope::current().unwrap();
+        (
+            scope.clone().get::<RequestId>().await.unwrap(),
+            scope.get::<Counter>().await.unwrap(),
+        )
+    })
+    .await
+    .unwrap();
+
+    assert!(Arc::ptr_eq(&parent, &child));
+    // Створене в задачі видно батьківському scope
+    assert!(Arc::ptr_eq(
+        &counter,
+        &scope.get::<Counter>().await.unwrap()
+    ));
+}
+
+#[rust_di::test]
+async fn test_spawn_fork_keeps_new_instances_in_the_child() {
+    register_request_id().await;
+    register_scope::<Counter, _, _>(|_| async { Ok(Counter) })
+        .await
+        .unwrap();
+    let scope = DIScope::current().unwrap();
+    let parent = scope.clone().get::<RequestId>().await.unwrap();
+
+    let (child, counter) = crate::spawn_with(ScopeMode::Fork, async {
+        let scope = DIScope::current().unwrap();
+        (
+            scope.clone().get::<RequestId>().await.unwrap(),
+            scope.get::<Counter>().await.unwrap(),
+        )
+    })
+    .await
+    .unwrap();
+
+    assert!(Arc::ptr_eq(&parent, &child));
+    assert!(!Arc::ptr_eq(
+        &counter,
+        &scope.get::<Counter>().await.unwrap()
+    ));
+}
+
+#[rust_di::test]
+async fn test_spawn_blocking_and_join_set_carry_the_scope() {
+    register_request_id().await;
+    let scope = DIScope::current().unwrap();
+    let parent = scope.clone().get::<RequestId>().await.unwrap();
+
+    let blocking = crate::spawn_blocking(|| DIScope::current().unwrap())
+        .await
+        .unwrap();
+    assert!(Arc::ptr_eq(&blocking, &scope));
+
+    let mut set = JoinSet::new();
+    for _ in 0..3 {
+        set.spawn_scoped(async {
+            DIScope::current()
+                .unwrap()
+                .get::<RequestId>()
+                .await
+                .unwrap()
+        });
+    }
+    while let Some(child) = set.join_next().await {
+        assert!(Arc::ptr_eq(&parent, &child.unwrap()));
+    }
+}
+
+#[tokio::test]
+async fn test_spawn_without_scope_opens_one_in_the_current_container() {
+    let container = Container::new();
+    let found = container
+        .clone()
+        .run(async {
+            register_request_id().await;
+            crate::spawn(async {
+                let scope = DIScope::current().unwrap();
+                assert!(Arc::ptr_eq(&Container::current(), &scope.container));
+                scope.get::<RequestId>().await.is_ok()
+            })
+            .await
+            .unwrap()
+        })
+        .await;
+    assert!(found);
+}
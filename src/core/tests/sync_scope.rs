@@ -0,0 +1,71 @@
+use crate::core::registry::{register_scope, register_singleton, register_transient};
+use crate::{DIScope, DiError};
+use std::sync::Arc;
+
+struct Session;
+
+struct Pricing {
+    rate: u64,
+}
+
+struct RemoteRates;
+
+async fn register_services() {
+    register_scope::<Session, _, _>(|_| async { Ok(Session) })
+        .await
+        .unwrap();
+    register_transient::<Pricing, _, _>(|_| async { Ok(Pricing { rate: 3 }) })
+        .await
+        .unwrap();
+    register_singleton::<RemoteRates, _, _>(|_| async {
+        tokio::task::yield_now().await;
+        Ok(RemoteRates)
+    })
+    .await
+    .unwrap();
+}
+
+#[rust_di::test]
+async fn test_enter_sync_makes_the_scope_current_on_os_threads() {
+    register_services().await;
+    let scope = DIScope::current().unwrap();
+    let session = scope.clone().get::<Session>().await.unwrap();
+
+    let from_threads: Vec<Arc<Session>> = std::thread::scope(|threads| {
+        let handles: Vec<_> = (0..3)
+            .map(|_| {
+                let scope = scope.clone();
+                threads.spawn(move || {
+                    scope.enter_sync(|| DIScope::current().unwrap().get_sync::<Session>().unwrap())
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert!(from_threads.iter().all(|s| Arc::ptr_eq(s, &session)));
+    assert!(
+        std::thread::spawn(|| DIScope::current().is_err())
+            .join()
+            .unwrap()
+    );
+}
+
+#[rust_di::test]
+async fn test_get_sync_builds_only_services_that_need_no_waiting() {
+    register_services().await;
+    let scope = DIScope::current().unwrap();
+
+    assert_eq!(scope.get_sync::<Pricing>().unwrap().rate, 3);
+    assert!(matches!(
+        scope.get_sync::<RemoteRates>(),
+        Err(DiError::WouldBlock(_))
+    ));
+
+    // Після асинхронного створення Singleton доступний синхронно
+    let rates = scope.clone().get::<RemoteRates>().await.unwrap();
+    assert!(Arc::ptr_eq(
+        &rates,
+        &scope.get_sync::<RemoteRates>().unwrap()
+    ));
+}
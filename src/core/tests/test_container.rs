@@ -0,0 +1,80 @@
+use crate::DIScope;
+use crate::core::container::Container;
+use crate::core::error_di::DiError;
+use crate::core::registry::register_singleton;
+
+struct IsolatedGreeter(&'static str);
+
+impl Default for IsolatedGreeter {
+    fn default() -> Self {
+        Self("real")
+    }
+}
+
+#[rust_di::registry(Singleton)]
+impl IsolatedGreeter {}
+
+struct IsolatedMailer(&'static str);
+
+async fn fake_greeter() -> Result<(), DiError> {
+    register_singleton::<IsolatedGreeter, _, _>(|_| async { Ok(IsolatedGreeter("fake")) }).await
+}
+
+async fn first_mailer() -> Result<(), DiError> {
+    register_singleton::<IsolatedMailer, _, _>(|_| async { Ok(IsolatedMailer("first")) }).await
+}
+
+async fn second_mailer() -> Result<(), DiError> {
+    register_singleton::<IsolatedMailer, _, _>(|_| async { Ok(IsolatedMailer("second")) }).await
+}
+
+#[rust_di::test]
+async fn test_isolated_container_is_seeded_from_inventory() {
+    let scope = DIScope::current().unwrap();
+    let greeter = scope.get::<IsolatedGreeter>().await.unwrap();
+    assert_eq!(greeter.0, "real");
+    assert!(!std::sync::Arc::ptr_eq(
+        &Container::current(),
+        &Container::global()
+    ));
+}
+
+#[rust_di::test(setup = fake_greeter)]
+async fn test_isolated_container_setup_overrides_inventory() {
+    let scope = DIScope::current().unwrap();
+    let greeter = scope.get::<IsolatedGreeter>().await.unwrap();
+    assert_eq!(greeter.0, "fake");
+}
+
+#[rust_di::test(setup = first_mailer)]
+async fn test_isolated_container_first_fake() {
+    let scope = DIScope::current().unwrap();
+    assert_eq!(scope.get::<IsolatedMailer>().await.unwrap().0, "first");
+}
+
+#[rust_di::test(setup = second_mailer, flavor = "multi_thread", worker_threads = 2)]
+async fn test_isolated_container_second_fake() -> Result<(), DiError> {
+    let scope = DIScope::current()?;
+    assert_eq!(scope.get::<IsolatedMailer>().await?.0, "second");
+
+    // Глобальний контейнер нічого не знає про fake
+    let global = Container::global()
+        .run(DIScope::run_with_scope(|| async {
+            DIScope::current()?.try_get::<IsolatedMailer>().await
+        }))
+        .await?;
+    assert!(global.is_none());
+    Ok(())
+}
+
+#[rust_di::test]
+async fn test_initialize_after_clear_registers_inventory_again() -> Result<(), DiError> {
+    let container = Container::current();
+    container.clear();
+    let scope = DIScope::current()?;
+    assert!(scope.clone().get::<IsolatedGreeter>().await.is_err());
+
+    container.initialize().await?;
+    assert_eq!(scope.get::<IsolatedGreeter>().await?.0, "real");
+    Ok(())
+}
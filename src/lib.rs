@@ -3,22 +3,93 @@ extern crate self as rust_di;
 
 pub mod core;
 
+pub use async_trait;
 pub use inventory;
+#[cfg(any(feature = "tokio", test))]
+#[doc(hidden)]
+pub use tokio;
 
 inventory::collect!(DiConstructor);
 
 pub use crate::core::error_di::DiError;
 pub use crate::core::factory::DiFactory;
+pub use crate::core::future_ext::{DiFutureExt, WithDiScope};
+pub use crate::core::inject::Inject;
+pub use crate::core::local::LocalScope;
+#[cfg(feature = "tokio")]
+pub use crate::core::task::{
+    ScopeMode, ScopedJoinSet, spawn, spawn_blocking, spawn_blocking_with, spawn_with,
+};
 
-use crate::core::contracts::{ScopedMap, ServiceInstance};
+use crate::core::container::Container;
+use crate::core::context::{ContextKey, context_local};
+use crate::core::contracts::{Lifetime, ScopedMap, ServiceFactory, ServiceInstance, ServiceKey};
 use crate::core::di_inventory::DiConstructor;
-use crate::core::registry::{
-    REGISTERED_SCOPE_FACTORIES, REGISTERED_SINGLETON_FACTORIES, REGISTERED_TRANSIENT_FACTORIES,
-    SINGLETON_CACHE,
-};
+use crate::core::open_generic::OpenGeneric;
+use crate::core::profiling::{self, StartupReport};
+use crate::core::registry::materialize_open_generic;
 use dashmap::DashMap;
+use futures::FutureExt;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::{cell::RefCell, fmt, future::Future, sync::Arc};
 
+/// Derive macro generating a [`DiFactory`] implementation from the struct fields.
+///
+/// * `Arc<T>` fields are resolved from the scope
+/// * `Option<Arc<T>>` fields are `None` when `T` is not registered
+/// * `#[inject(name = "...")]` resolves a named registration
+/// * `#[inject(open)]` resolves a member of an open-generic family
+/// * `#[inject(default)]` uses `Default::default()`
+/// * `#[inject(with = expr)]` uses the given expression
+///
+/// ```ignore
+/// #[derive(rust_di::Injectable)]
+/// struct Processor {
+///     logger: Arc<Logger>,
+///     #[inject(name = "audit")]
+///     audit: Option<Arc<Logger>>,
+///     #[inject(default)]
+///     processed: AtomicUsize,
+/// }
+///
+/// #[rust_di::registry(Singleton(factory))]
+/// impl Processor {}
+/// ```
+pub use di_macros::Injectable;
+/// Registers the return type of an async function, resolving its parameters from the scope.
+///
+/// Parameters follow the `Injectable` rules (`Arc<T>`, `Option<Arc<T>>`, `#[inject(...)]`).
+/// The function may return the service or `Result<Service, DiError>`.
+///
+/// ```ignore
+/// #[rust_di::factory(Singleton, name = "primary")]
+/// async fn make_pool(cfg: Arc<DbConfig>) -> Result<PgPool, DiError> {
+///     PgPool::connect(&cfg.url).await.map_err(|e| DiError::FactoryError(Box::new(e)))
+/// }
+/// ```
+pub use di_macros::factory;
+/// Resolves `Inject<T>` and `#[inject(...)]` parameters of an async function from
+/// the current scope before the body runs; the remaining parameters stay with the caller.
+///
+/// Resolution errors are returned through `From<DiError>` when the function returns
+/// a `Result`, otherwise they panic.
+///
+/// ```ignore
+/// #[rust_di::inject]
+/// async fn handle(
+///     request: Request,
+///     logger: Inject<Logger>,
+///     #[inject(name = "audit")] audit: Arc<Logger>,
+/// ) -> Result<Response, DiError> {
+///     logger.log(&request.path);
+///     ...
+/// }
+///
+/// handle(request).await?;
+/// ```
+///
+/// `#[with_di_scope]` accepts the same parameters and resolves them inside its new scope.
+pub use di_macros::inject;
 pub use di_macros::main;
 /// Attribute macro for registering services.
 ///
@@ -30,6 +101,12 @@ pub use di_macros::main;
 ///     Singleton(factory),
 ///     Singleton(name = "custom"),
 ///     Singleton(factory = MyFactory, name = "custom"),
+///     Singleton(eager),
+///     Singleton(if_missing),
+///     Singleton(timeout = "5s"),
+///     Singleton(retry(attempts = 5, backoff = "100ms", jitter = 0.2, cooldown = "30s")),
+///     Singleton(factory, fallback = InMemoryCache),  // resolved, then `From<Arc<InMemoryCache>>`
+///     Singleton(factory, fallback = "replica"),      // the same type under another name
 ///
 ///     Transient,
 ///     Transient(factory),
@@ -42,30 +119,110 @@ pub use di_macros::main;
 ///     Scoped(factory = MyFactory, name = "custom"),
 /// )]
 /// impl MyService {}
+///
+/// // Generic impl blocks list the concrete types to register
+/// #[rust_di::registry(Singleton, for = [Repo<User>, Repo<Order>])]
+/// impl<T: Entity> Repo<T> {}
+///
+/// // ...or register the whole family, resolved with `DIScope::get_open`
+/// #[rust_di::registry(Singleton(factory), open)]
+/// impl<T: Entity> Repo<T> {}
+///
+/// // `initialize()` applies higher priorities first, and `after` another service
+/// #[rust_di::registry(Singleton, priority = 10, after = ConsoleSink)]
+/// impl AuditSink {}
 /// ```
+///
+/// The list above shows the accepted forms; in a real attribute each name may
+/// appear only once, since a key has a single lifetime. Unknown kinds or options
+/// are compile errors, and registrations without `factory` require `Default`.
 pub use di_macros::registry;
+/// Runs an async test on its own [`Container`],
+/// so tests can register different fakes for the same type and run in parallel.
+///
+/// The container is seeded from the `setup` functions first and then from the
+/// `#[registry]` / `#[factory]` registrations, whose duplicates are ignored:
+/// whatever `setup` registers wins. The body runs inside a fresh `DIScope`;
+/// the container is cleared afterwards. `flavor` and `worker_threads` are
+/// forwarded to `#[tokio::test]`. Requires the `tokio` feature.
+///
+/// ```ignore
+/// async fn fake_mailer() -> Result<(), DiError> {
+///     register_singleton::<Mailer, _, _>(|_| async { Ok(Mailer::fake()) }).await
+/// }
+///
+/// #[rust_di::test(setup = fake_mailer)]
+/// async fn sends_welcome_mail() {
+///     let mailer = DIScope::current().unwrap().get::<Mailer>().await.unwrap();
+///     assert!(mailer.is_fake());
+/// }
+/// ```
+pub use di_macros::test;
 pub use di_macros::with_di_scope;
 
-use tokio::sync::OnceCell;
+/// Registers every `#[registry]` service and constructs the eager Singletons.
+///
+/// # Panics
+///
+/// Panics if an eager Singleton fails to construct. Use [`try_initialize`] to handle the error.
+pub async fn initialize() {
+    if let Err(e) = try_initialize().await {
+        panic!("rust_di initialization failed: {e}");
+    }
+}
+
+/// Registers every `#[registry]` service and constructs the eager Singletons,
+/// returning the first construction error.
+///
+/// A failed warm-up is retried by the next call. Works on [`Container::current`].
+pub async fn try_initialize() -> Result<(), DiError> {
+    Container::current().initialize().await
+}
 
-static INIT: OnceCell<()> = OnceCell::const_new();
+/// Runs [`try_initialize`] with startup profiling enabled and returns the
+/// construction times of every Singleton built meanwhile.
+pub async fn initialize_with_report() -> Result<StartupReport, DiError> {
+    profiling::start_startup_profiling();
+    let result = try_initialize().await;
+    let report = profiling::finish_startup_profiling();
+    result.map(|_| report)
+}
 
-pub async fn initialize() {
-    INIT.get_or_init(|| async {
-        for ctor in inventory::iter::<DiConstructor> {
-            (ctor.init)().await;
-        }
-    })
-    .await;
+/// Constructs every Singleton registered as eager that is not cached yet.
+///
+/// Eager Singletons are built concurrently, each inside its own scope, at most
+/// [`Container::init_concurrency`] at a time.
+/// `initialize()` calls this once; call it again after registering eager
+/// Singletons manually.
+pub async fn warm_up() -> Result<(), DiError> {
+    Container::current().warm_up().await
 }
 
-tokio::task_local! {
+context_local! {
     static CURRENT_DI_SCOPE: Arc<DIScope>;
-    static RESOLVING_STACK: RefCell<Vec<String>>;
+    static RESOLVING_STACK: RefCell<ResolvingStack>;
+}
+
+/// Keys being resolved by one chain of nested `get`s.
+pub(crate) struct ResolvingStack {
+    /// Identifies the chain while it waits for a Singleton built by another one.
+    id: u64,
+    keys: Vec<ServiceKey>,
+}
+
+impl ResolvingStack {
+    fn new() -> RefCell<Self> {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        RefCell::new(ResolvingStack {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            keys: Vec::new(),
+        })
+    }
 }
 
 pub struct DIScope {
     pub scoped_instances: Arc<ScopedMap>,
+    pub(crate) container: Arc<Container>,
 }
 
 impl Drop for DIScope {
@@ -78,30 +235,41 @@ impl fmt::Debug for DIScope {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("DIScope")
             .field("scoped_instances_count", &self.scoped_instances.len())
+            .field("container", &self.container)
             .finish()
     }
 }
 
 impl DIScope {
+    /// Creates a scope over [`Container::current`].
     pub async fn new() -> Arc<Self> {
-        REGISTERED_SINGLETON_FACTORIES
-            .get_or_init(|| async { arc_swap::ArcSwap::from_pointee(DashMap::new()) })
-            .await;
-        REGISTERED_TRANSIENT_FACTORIES
-            .get_or_init(|| async { arc_swap::ArcSwap::from_pointee(DashMap::new()) })
-            .await;
-        REGISTERED_SCOPE_FACTORIES
-            .get_or_init(|| async { arc_swap::ArcSwap::from_pointee(DashMap::new()) })
-            .await;
+        Self::over(Container::current())
+    }
 
+    pub(crate) fn over(container: Arc<Container>) -> Arc<Self> {
         Arc::new(DIScope {
             scoped_instances: Arc::new(DashMap::new()),
+            container,
+        })
+    }
+
+    /// Creates a child scope over the same container that starts with the Scoped
+    /// instances built so far; the ones it builds later are not shared back.
+    pub fn fork(&self) -> Arc<Self> {
+        Arc::new(DIScope {
+            scoped_instances: Arc::new(
+                self.scoped_instances
+                    .iter()
+                    .map(|entry| (*entry.key(), entry.value().clone()))
+                    .collect(),
+            ),
+            container: self.container.clone(),
         })
     }
 
     pub fn current() -> Result<Arc<DIScope>, DiError> {
         CURRENT_DI_SCOPE
-            .try_with(|scope| scope.clone())
+            .with_current(|scope| scope.clone())
             .map_err(|e| {
                 DiError::FactoryError(Box::new(std::io::Error::other(format!(
                     "No DI scope found in this task: {e}",
@@ -115,13 +283,51 @@ impl DIScope {
         RFut: Future<Output = ROutput>,
     {
         let scope = DIScope::new().await;
+        #[cfg(feature = "metrics")]
+        let _live_scope = crate::core::metrics::LiveScopeGuard::new();
         RESOLVING_STACK
-            .scope(RefCell::new(Vec::new()), async {
-                CURRENT_DI_SCOPE.scope(scope.clone(), func()).await
+            .scope_future(ResolvingStack::new(), async {
+                CURRENT_DI_SCOPE.scope_future(scope.clone(), func()).await
             })
             .await
     }
 
+    /// Runs `fut` with this scope as [`DIScope::current`] and its container as
+    /// [`Container::current`], without creating a new scope.
+    ///
+    /// Every `enter` starts with its own circular-dependency stack, so the same
+    /// scope can be entered by several futures at once.
+    pub fn enter<F: Future>(self: &Arc<Self>, fut: F) -> WithDiScope<F> {
+        let fut = CURRENT_DI_SCOPE.scope_future(self.clone(), fut);
+        let fut = RESOLVING_STACK.scope_future(ResolvingStack::new(), fut);
+        WithDiScope::new(self.container.clone().scope(fut))
+    }
+
+    /// Runs `f` on this thread with this scope as [`DIScope::current`], e.g. in
+    /// `std::thread::scope` or a blocking task; see also [`DIScope::get_sync`].
+    pub fn enter_sync<R>(self: &Arc<Self>, f: impl FnOnce() -> R) -> R {
+        let scope = self.clone();
+        self.container.clone().run_sync(|| {
+            RESOLVING_STACK.scope_sync(ResolvingStack::new(), || {
+                CURRENT_DI_SCOPE.scope_sync(scope, f)
+            })
+        })
+    }
+
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    async fn construct(
+        factory: &ServiceFactory,
+        scope: Arc<DIScope>,
+        key: &ServiceKey,
+        lifetime: Lifetime,
+    ) -> Result<ServiceInstance, DiError> {
+        #[cfg(feature = "metrics")]
+        return crate::core::metrics::measure_factory(key, lifetime, factory(scope)).await;
+
+        #[cfg(not(feature = "metrics"))]
+        factory(scope).await
+    }
+
     pub async fn get<T>(self: Arc<Self>) -> Result<Arc<T>, DiError>
     where
         T: Send + Sync + 'static,
@@ -133,92 +339,241 @@ impl DIScope {
     where
         T: Send + Sync + 'static,
     {
-        let type_key = std::any::type_name::<T>().to_string();
-        let name_string = name.to_string();
-        let key = (type_key.clone(), name_string.clone());
+        let Some(key) = ServiceKey::lookup::<T>(name) else {
+            // Ім'я ніколи не реєструвалось, тож ключа немає
+            let error = DiError::ServiceNotFound(name.to_string());
+            #[cfg(feature = "metrics")]
+            crate::core::metrics::record_resolution_failure(
+                std::any::type_name::<T>(),
+                name,
+                &error,
+            );
+            return Err(error);
+        };
+        let instance = self.resolve(key).await?;
+
+        let any_instance: Arc<dyn std::any::Any + Send + Sync> = instance;
+        any_instance.downcast::<T>().map_err(|_| {
+            DiError::FactoryError(Box::new(std::io::Error::other(format!(
+                "Type mismatch: could not downcast to {}",
+                std::any::type_name::<T>()
+            ))))
+        })
+    }
 
-        // Захист від циклічних залежностей
-        RESOLVING_STACK
-            .try_with(|stack| {
-                let mut stack_ref = stack.borrow_mut();
-                if stack_ref.contains(&type_key) {
-                    return Err(DiError::CircularDependency(name_string.clone()));
-                }
-                stack_ref.push(type_key.clone());
-                Ok(())
-            })
-            .map_err(|e| {
+    /// Resolves `T` without an async runtime: cached Singletons and Scoped
+    /// instances, and services whose factory completes without waiting.
+    ///
+    /// A factory that has to wait fails with `DiError::WouldBlock` and is dropped.
+    pub fn get_sync<T>(self: &Arc<Self>) -> Result<Arc<T>, DiError>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.get_sync_by_name::<T>("")
+    }
+
+    pub fn get_sync_by_name<T>(self: &Arc<Self>, name: &str) -> Result<Arc<T>, DiError>
+    where
+        T: Send + Sync + 'static,
+    {
+        let resolving = self.clone().get_by_name::<T>(name);
+        self.enter_sync(|| resolving.now_or_never())
+            .unwrap_or_else(|| Err(DiError::WouldBlock(std::any::type_name::<T>().to_string())))
+    }
+
+    pub async fn get_open<T>(self: Arc<Self>) -> Result<Arc<T>, DiError>
+    where
+        T: DiFactory + OpenGeneric,
+    {
+        self.get_open_by_name::<T>("").await
+    }
+
+    /// Resolves a member of an open-generic family, e.g. `Repo<Invoice>` for
+    /// `#[registry(Singleton, open)] impl<T: Entity> Repo<T> {}`.
+    ///
+    /// The first call registers `T` with the family's lifetime and builds it
+    /// through its `DiFactory`; afterwards `T` behaves like a regular registration.
+    /// Until then `get::<T>()` returns `ServiceNotFound`: it has no `DiFactory`
+    /// bound to build `T` with.
+    /// Closed registrations of `T` take precedence over the family.
+    pub async fn get_open_by_name<T>(self: Arc<Self>, name: &str) -> Result<Arc<T>, DiError>
+    where
+        T: DiFactory + OpenGeneric,
+    {
+        let key = ServiceKey::of::<T>(name);
+        if !self.is_registered(&key) {
+            materialize_open_generic::<T>(&self.container, name).await?;
+        }
+        self.get_by_name::<T>(name).await
+    }
+
+    pub async fn try_get<T>(self: Arc<Self>) -> Result<Option<Arc<T>>, DiError>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.try_get_by_name::<T>("").await
+    }
+
+    /// Like [`DIScope::get_by_name`], but returns `Ok(None)` when the service is not registered.
+    ///
+    /// Errors of a registered service (including missing dependencies) are still returned.
+    pub async fn try_get_by_name<T>(self: Arc<Self>, name: &str) -> Result<Option<Arc<T>>, DiError>
+    where
+        T: Send + Sync + 'static,
+    {
+        if !ServiceKey::lookup::<T>(name).is_some_and(|key| self.is_registered(&key)) {
+            return Ok(None);
+        }
+        self.get_by_name::<T>(name).await.map(Some)
+    }
+
+    pub async fn get_all<T>(self: Arc<Self>) -> Result<Vec<Arc<T>>, DiError>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.get_all_by_name::<T>("").await
+    }
+
+    /// Resolves every registration of `T` under `name` in registration order:
+    /// the first one plus those added with `DuplicatePolicy::Append`.
+    ///
+    /// Returns an empty `Vec` when `T` is not registered.
+    pub async fn get_all_by_name<T>(self: Arc<Self>, name: &str) -> Result<Vec<Arc<T>>, DiError>
+    where
+        T: Send + Sync + 'static,
+    {
+        let Some(key) = ServiceKey::lookup::<T>(name).filter(|key| self.is_registered(key)) else {
+            return Ok(Vec::new());
+        };
+
+        let mut services = Vec::new();
+        for key in self.container.binding_keys(&key) {
+            let instance: Arc<dyn std::any::Any + Send + Sync> = self.clone().resolve(key).await?;
+            services.push(instance.downcast::<T>().map_err(|_| {
                 DiError::FactoryError(Box::new(std::io::Error::other(format!(
-                    "Failed to access resolving stack: {e}",
+                    "Type mismatch: could not downcast to {}",
+                    std::any::type_name::<T>()
                 ))))
-            })??;
+            })?);
+        }
+        Ok(services)
+    }
 
-        let result: Result<ServiceInstance, DiError> = async {
-            // 🔁 Scoped (в межах поточного DIScope)
-            {
-                if let Some(entry) = self.scoped_instances.get(&key) {
-                    return Ok(entry.value().clone());
-                }
-                if let Some(factories) = REGISTERED_SCOPE_FACTORIES.get()
-                    && let Some(factory) = factories.load().get(&key)
-                {
-                    let instance = factory.value()(self.clone()).await?;
-                    self.scoped_instances.insert(key.clone(), instance.clone());
-                    return Ok(instance);
+    fn is_registered(&self, key: &ServiceKey) -> bool {
+        self.scoped_instances.contains_key(key) || self.container.is_registered(key)
+    }
+
+    pub(crate) async fn resolve(
+        self: Arc<Self>,
+        key: ServiceKey,
+    ) -> Result<ServiceInstance, DiError> {
+        // Захист від циклічних залежностей
+        let resolving = Resolving::enter(key)?;
+
+        let result: Result<(ServiceInstance, Lifetime, Option<bool>), DiError> = async {
+            let Some((lifetime, factory)) = self.container.lookup(&key) else {
+                return Err(DiError::ServiceNotFound(key.name().to_string()));
+            };
+
+            match lifetime {
+                // 🔁 Scoped (в межах поточного DIScope)
+                Lifetime::Scoped => {
+                    if let Some(entry) = self.scoped_instances.get(&key) {
+                        return Ok((entry.value().clone(), lifetime, Some(true)));
+                    }
+                    let instance = Self::construct(&factory, self.clone(), &key, lifetime).await?;
+                    self.scoped_instances.insert(key, instance.clone());
+                    Ok((instance, lifetime, Some(false)))
                 }
-            }
 
-            // 🔁 Singleton (глобальний кеш)
-            {
-                if let Some(factories) = REGISTERED_SINGLETON_FACTORIES.get()
-                    && let Some(factory) = factories.load().get(&key)
-                {
-                    let cache = SINGLETON_CACHE.get_or_init(DashMap::new);
+                // 🔁 Singleton (глобальний кеш)
+                Lifetime::Singleton => {
+                    let cache = &self.container.singleton_cache;
+                    if let Some(cached) = cache.get(&key) {
+                        return Ok((cached.value().clone(), lifetime, Some(true)));
+                    }
+                    // Паралельні виклики чекають на першого, а не будують свій екземпляр
+                    let _building = self
+                        .container
+                        .lock_singleton(key, resolving.resolution)
+                        .await?;
                     if let Some(cached) = cache.get(&key) {
-                        return Ok(cached.value().clone());
+                        return Ok((cached.value().clone(), lifetime, Some(true)));
                     }
-                    let instance = factory.value()(self.clone()).await?;
-                    cache.insert(key.clone(), instance.clone());
-                    return Ok(instance);
+                    let instance = profiling::measure_singleton(
+                        &key,
+                        Self::construct(&factory, self.clone(), &key, lifetime),
+                    )
+                    .await?;
+                    cache.insert(key, instance.clone());
+                    // Реєстрацію замінили під час побудови: екземпляр старої фабрики не кешується
+                    if !self.container.is_current(&key, &factory) {
+                        cache.remove_if(&key, |_, cached| Arc::ptr_eq(cached, &instance));
+                    }
+                    Ok((instance, lifetime, Some(false)))
                 }
-            }
 
-            // 🔁 Transient (новий кожного разу)
-            {
-                if let Some(factories) = REGISTERED_TRANSIENT_FACTORIES.get()
-                    && let Some(factory) = factories.load().get(&key)
-                {
-                    let instance = factory.value()(self.clone()).await?;
-                    return Ok(instance);
+                // 🔁 Transient (новий кожного разу)
+                Lifetime::Transient => {
+                    let instance = Self::construct(&factory, self.clone(), &key, lifetime).await?;
+                    Ok((instance, lifetime, None))
                 }
             }
-
-            Err(DiError::ServiceNotFound(name_string.clone()))
         }
         .await;
 
-        // Знімаємо з resolving stack
+        #[cfg(feature = "metrics")]
+        match &result {
+            Ok((_, lifetime, cache_hit)) => {
+                crate::core::metrics::record_resolution(&key, *lifetime, *cache_hit)
+            }
+            Err(error) => {
+                crate::core::metrics::record_resolution_failure(key.type_name(), key.name(), error)
+            }
+        }
+
+        result.map(|(instance, _, _)| instance)
+    }
+}
+
+/// Marks `key` as being resolved by this task until dropped, so that an
+/// abandoned resolution doesn't leave it on the stack.
+struct Resolving {
+    key: ServiceKey,
+    /// Id of the resolution chain, see [`ResolvingStack`].
+    resolution: u64,
+}
+
+impl Resolving {
+    fn enter(key: ServiceKey) -> Result<Self, DiError> {
         RESOLVING_STACK
-            .try_with(|stack| {
-                stack.borrow_mut().pop();
-                Ok(())
+            .with_current(|stack| {
+                let mut stack = stack.borrow_mut();
+                if stack.keys.contains(&key) {
+                    return Err(DiError::CircularDependency(key.name().to_string()));
+                }
+                stack.keys.push(key);
+                Ok(Resolving {
+                    key,
+                    resolution: stack.id,
+                })
             })
             .map_err(|e| {
                 DiError::FactoryError(Box::new(std::io::Error::other(format!(
                     "Failed to access resolving stack: {e}",
                 ))))
-            })??;
-
-        result.and_then(|instance| {
-            let any_instance: Arc<dyn std::any::Any + Send + Sync> = instance;
+            })?
+    }
+}
 
-            any_instance.downcast::<T>().map_err(|_| {
-                DiError::FactoryError(Box::new(std::io::Error::other(format!(
-                    "Type mismatch: could not downcast to {}",
-                    std::any::type_name::<T>()
-                ))))
-            })
-        })
+impl Drop for Resolving {
+    fn drop(&mut self) {
+        let _ = RESOLVING_STACK.with_current(|stack| {
+            let mut stack = stack.borrow_mut();
+            if let Some(position) = stack.keys.iter().rposition(|key| *key == self.key) {
+                stack.keys.remove(position);
+            }
+        });
     }
 }
 
@@ -404,6 +759,53 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_warm_up_builds_eager_singletons_concurrently_and_reports_errors() {
+        initialize().await;
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+
+        struct EagerLeft;
+        struct EagerRight;
+        struct EagerBroken;
+
+        // Обидві фабрики чекають одна на одну: послідовно це зависло б
+        let left_barrier = barrier.clone();
+        register_singleton_eager::<EagerLeft, _, _>(move |_| {
+            let barrier = left_barrier.clone();
+            async move {
+                barrier.wait().await;
+                Ok(EagerLeft)
+            }
+        })
+        .await
+        .unwrap();
+        register_singleton_eager::<EagerRight, _, _>(move |_| {
+            let barrier = barrier.clone();
+            async move {
+                barrier.wait().await;
+                Ok(EagerRight)
+            }
+        })
+        .await
+        .unwrap();
+
+        tokio::time::timeout(std::time::Duration::from_secs(5), warm_up())
+            .await
+            .expect("eager singletons were not built concurrently")
+            .unwrap();
+
+        register_singleton_eager::<EagerBroken, _, _>(|_| async {
+            Err::<EagerBroken, _>(DiError::ServiceNotFound("database".to_string()))
+        })
+        .await
+        .unwrap();
+
+        assert!(matches!(
+            warm_up().await,
+            Err(DiError::ServiceNotFound(name)) if name == "database"
+        ));
+    }
+
     #[tokio::test]
     async fn test_scope_drop_clears_instances() {
         initialize().await;
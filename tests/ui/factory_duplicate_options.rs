@@ -0,0 +1,30 @@
+use rust_di::DiError;
+
+struct Pool;
+
+#[rust_di::factory(Singleton, eager, eager)]
+async fn eager_pool() -> Result<Pool, DiError> {
+    Ok(Pool)
+}
+
+#[rust_di::factory(Singleton, if_missing, if_missing)]
+async fn default_pool() -> Result<Pool, DiError> {
+    Ok(Pool)
+}
+
+#[rust_di::factory(Singleton, timeout = "1s", timeout = "2s")]
+async fn slow_pool() -> Result<Pool, DiError> {
+    Ok(Pool)
+}
+
+#[rust_di::factory(Singleton, retry(attempts = 3), retry(attempts = 5))]
+async fn flaky_pool() -> Result<Pool, DiError> {
+    Ok(Pool)
+}
+
+#[rust_di::factory(Singleton, name = "primary", name = "replica")]
+async fn named_pool() -> Result<Pool, DiError> {
+    Ok(Pool)
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#[derive(Default)]
+struct Cache;
+
+#[rust_di::registry(Singleton, Singleton)]
+impl Cache {}
+
+#[derive(Default)]
+struct Pool;
+
+#[rust_di::registry(Scoped(name = "primary"), Transient(name = "primary"))]
+impl Pool {}
+
+fn main() {}
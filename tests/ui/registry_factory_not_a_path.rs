@@ -0,0 +1,6 @@
+struct Pool;
+
+#[rust_di::registry(Singleton(factory = "PoolFactory"))]
+impl Pool {}
+
+fn main() {}
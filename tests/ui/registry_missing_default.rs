@@ -0,0 +1,8 @@
+struct Pool {
+    size: usize,
+}
+
+#[rust_di::registry(Singleton)]
+impl Pool {}
+
+fn main() {}
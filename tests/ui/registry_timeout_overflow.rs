@@ -0,0 +1,7 @@
+#[derive(Default)]
+struct Pool;
+
+#[rust_di::registry(Singleton(timeout = "18446744073709551615h"))]
+impl Pool {}
+
+fn main() {}
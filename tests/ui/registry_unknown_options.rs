@@ -0,0 +1,11 @@
+struct Cache;
+
+#[rust_di::registry(Singelton)]
+impl Cache {}
+
+struct Pool;
+
+#[rust_di::registry(Singleton(eagre))]
+impl Pool {}
+
+fn main() {}